@@ -1,10 +1,35 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::Path;
 
+use chrono::{DateTime, Duration, Local, NaiveDate};
 use serde::{de::IgnoredAny, Deserialize, Deserializer, Serialize};
-use sysinfo::{NetworkExt, NetworksExt, ProcessExt, System, SystemExt};
+use sysinfo::{ProcessExt, System, SystemExt};
+
+use crate::{
+    archive::ArchivedTask,
+    currency::{default_currency, format_amount, ExchangeRate},
+    history::HistoryEntry,
+    reward_collection::RewardCollection,
+    settings::Settings,
+    streak::Streak,
+    task,
+    template::TaskTemplate,
+};
+// Drains this small are rounding noise of rates that cancel out
+const MIN_HOURLY_DRAIN: f64 = 1e-9;
+// Projections further out than a century are shown as never
+const MAX_PROJECTION_HOURS: f64 = 100.0 * 365.0 * 24.0;
+
+// Matches process names case-insensitively, so "code" also finds "Code.exe"
+pub fn processes_running(sys: &System, names: &[String]) -> bool {
+    return sys.processes().values().any(|process| {
+        let process_name = process.name().to_lowercase();
+        names
+            .iter()
+            .any(|name| process_name.contains(&name.to_lowercase()))
+    });
+}
 
-use crate::{crypto_utils, reward_collection::RewardCollection, task};
 pub fn get_app_state_filepath() -> &'static str {
     return "./state";
 }
@@ -16,10 +41,28 @@ pub fn initialize_default_app_state() {
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct AppState {
-    pub cur_points: f64,
+    #[serde(default)]
+    pub balances: BTreeMap<String, f64>,
+    // States saved before currencies were introduced hold a single balance
+    #[serde(default, rename = "cur_points", skip_serializing)]
+    legacy_points: Option<f64>,
+    #[serde(default)]
+    pub exchange_rates: Vec<ExchangeRate>,
     pub tasks: Vec<task::Task>,
     pub rewards: Vec<RewardCollection>,
     pub folder_pool: Vec<String>,
+    #[serde(default)]
+    pub archive: Vec<ArchivedTask>,
+    #[serde(default)]
+    pub templates: Vec<TaskTemplate>,
+    #[serde(default)]
+    pub completion_streak: Streak,
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(default)]
+    pub last_interest_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub history: Vec<HistoryEntry>,
     #[serde(default, deserialize_with = "skip", skip_serializing)]
     pub sys: System,
 }
@@ -37,10 +80,18 @@ where
 impl AppState {
     fn get_default() -> AppState {
         return AppState {
-            cur_points: (0.0),
+            balances: BTreeMap::new(),
+            legacy_points: None,
+            exchange_rates: Vec::new(),
             tasks: Vec::new(),
             folder_pool: Vec::new(),
             rewards: Vec::new(),
+            archive: Vec::new(),
+            templates: Vec::new(),
+            completion_streak: Streak::default(),
+            settings: Settings::default(),
+            last_interest_date: None,
+            history: Vec::new(),
             sys: System::new_all(),
         };
     }
@@ -48,15 +99,355 @@ impl AppState {
     pub fn load_from_disk() -> AppState {
         let contents = fs::read_to_string(get_app_state_filepath()).expect("read from file failed");
         let mut state: AppState = serde_json::from_str(&contents).unwrap();
+        state.migrate_legacy_points();
         state.sys.refresh_all();
         return state;
     }
 
+    // Skips the process scan and never writes, for read-only commands that run every few seconds
+    pub fn read_from_disk() -> Option<AppState> {
+        let contents = fs::read_to_string(get_app_state_filepath()).ok()?;
+        let mut state: AppState = serde_json::from_str(&contents).ok()?;
+        state.migrate_legacy_points();
+        return Some(state);
+    }
+
+    fn migrate_legacy_points(&mut self) {
+        if let Some(points) = self.legacy_points.take() {
+            self.add_points(&default_currency(), points);
+        }
+    }
+
+    pub fn balance(&self, currency: &str) -> f64 {
+        return *self.balances.get(currency).unwrap_or(&0.0);
+    }
+
+    pub fn add_points(&mut self, currency: &str, amount: f64) {
+        *self.balances.entry(currency.to_string()).or_insert(0.0) += amount;
+    }
+
+    pub fn format_balances(&self) -> String {
+        if self.balances.is_empty() {
+            return format_amount(0.0, &default_currency());
+        }
+        return self
+            .balances
+            .iter()
+            .map(|(currency, amount)| format_amount(*amount, currency))
+            .collect::<Vec<String>>()
+            .join(", ");
+    }
+
+    // Tasks that depended on the removed one stop waiting for it
+    pub fn remove_task(&mut self, index: usize) -> task::Task {
+        let removed = self.tasks.remove(index);
+        for task in &mut self.tasks {
+            task.depends_on.retain(|prerequisite| *prerequisite != removed.name);
+        }
+        return removed;
+    }
+
+    pub fn is_any_process_running(&self, names: &[String]) -> bool {
+        return processes_running(&self.sys, names);
+    }
+
+    // Lets running tasks note whether their work processes are up, so idle sessions are not judged on the last moment only
+    pub fn sample_work_processes(&mut self) {
+        let now = Local::now();
+        let is_due = self.tasks.iter().any(|task| {
+            task.running_since().is_some()
+                && !task.work_processes.is_empty()
+                && task.work_log.running_samples.is_due(now)
+        });
+        if !is_due {
+            return;
+        }
+        self.sys.refresh_processes();
+        for task in &mut self.tasks {
+            task.sample_work_processes(now, &self.sys);
+        }
+    }
+
+    pub fn exchange_rate(&self, from: &str, to: &str) -> Option<f64> {
+        return self
+            .exchange_rates
+            .iter()
+            .find(|rate| rate.from == from && rate.to == to)
+            .map(|rate| rate.rate);
+    }
+
+    pub fn accrue_debt_interest(&mut self) {
+        self.accrue_debt_interest_on(Local::now().date_naive());
+    }
+
+    // Compounds the debt once for every day passed since the last accrual
+    fn accrue_debt_interest_on(&mut self, today: NaiveDate) {
+        let days_passed = match self.last_interest_date {
+            Some(last_date) => today.signed_duration_since(last_date).num_days(),
+            None => 0,
+        };
+        self.last_interest_date = Some(today);
+        if days_passed <= 0 || self.settings.debt_interest_rate <= 0.0 {
+            return;
+        }
+        let growth = (1.0 + self.settings.debt_interest_rate).powi(days_passed as i32);
+        for (currency, amount) in self.balances.iter_mut().filter(|(_, amount)| **amount < 0.0) {
+            let debt_before = -*amount;
+            *amount *= growth;
+            println!(
+                "Debt interest for {} day(s): {} debt grew from {} to {}",
+                days_passed, currency, debt_before, -*amount
+            );
+            self.history
+                .push(HistoryEntry::debt_interest(currency, -*amount - debt_before));
+        }
+    }
+
+    pub fn is_over_debt_ceiling(&self, currency: &str) -> bool {
+        return match self.settings.debt_ceiling {
+            Some(ceiling) => -self.balance(currency) > ceiling,
+            None => false,
+        };
+    }
+
+    // Currencies that running hourly tasks or rented rewards are adding to or taking from
+    pub fn currencies_with_timers(&self) -> BTreeSet<String> {
+        let task_currencies = self
+            .tasks
+            .iter()
+            .filter(|task| task.running_since().is_some() || task.is_paused())
+            .map(|task| task.currency.clone());
+        let reward_currencies = self
+            .rewards
+            .iter()
+            .filter(|reward| reward.rented_since().is_some())
+            .map(|reward| reward.currency.clone());
+        return task_currencies.chain(reward_currencies).collect();
+    }
+
+    // The settled balance plus what finishing every task and returning every rental now would settle
+    pub fn projected_balance(&self, currency: &str, now: DateTime<Local>) -> f64 {
+        let earned: f64 = self
+            .tasks
+            .iter()
+            .filter(|task| task.currency == currency)
+            .map(|task| task.accrued_reward(now, &self.settings))
+            .sum();
+        let spent: f64 = self
+            .rewards
+            .iter()
+            .filter(|reward| reward.currency == currency)
+            .map(|reward| reward.accrued_cost(now))
+            .sum();
+        return self.balance(currency) + earned - spent;
+    }
+
+    // Points per hour the running rentals take, minus what the running tasks earn meanwhile
+    pub fn hourly_drain(&self, currency: &str, now: DateTime<Local>) -> f64 {
+        let earning: f64 = self
+            .tasks
+            .iter()
+            .filter(|task| task.currency == currency)
+            .map(|task| task.hourly_rate(now, &self.settings))
+            .sum();
+        let spending: f64 = self
+            .rewards
+            .iter()
+            .filter(|reward| reward.currency == currency && reward.rented_since().is_some())
+            .map(|reward| reward.cost)
+            .sum();
+        return spending - earning;
+    }
+
+    // None when the balance is not shrinking, or is already at or below zero.
+    // The drain is constant between the moments running tasks hit their session limits and stop earning.
+    pub fn projected_zero_time(&self, currency: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut balance = self.projected_balance(currency, now);
+        if balance <= 0.0 {
+            return None;
+        }
+        let mut cap_times: Vec<DateTime<Local>> = self
+            .tasks
+            .iter()
+            .filter(|task| task.currency == currency)
+            .filter_map(|task| task.session_cap_time(&self.settings))
+            .filter(|cap_time| *cap_time > now)
+            .collect();
+        cap_times.sort();
+        let mut segment_start = now;
+        for segment_end in cap_times.into_iter().map(Some).chain(std::iter::once(None)) {
+            let drain = self.hourly_drain(currency, segment_start);
+            let is_draining = drain > MIN_HOURLY_DRAIN;
+            let hours_to_zero = balance / drain;
+            let segment_hours = segment_end
+                .map(|end| end.signed_duration_since(segment_start).num_seconds() as f64 / 3600.0);
+            match segment_hours {
+                Some(hours) if !is_draining || hours_to_zero > hours => {
+                    balance -= drain * hours;
+                    segment_start = segment_end.unwrap();
+                }
+                _ if !is_draining || hours_to_zero > MAX_PROJECTION_HOURS => return None,
+                _ => return Some(segment_start + Duration::seconds((hours_to_zero * 3600.0) as i64)),
+            }
+        }
+        return None;
+    }
+
+    // Writes a temporary file first, so an interrupted save never leaves a truncated state behind
     pub fn save_on_disk(&self) {
-        fs::write(
-            get_app_state_filepath(),
-            serde_json::to_string(&self).unwrap(),
-        )
-        .expect("write to file failed");
+        let temp_filepath = f!("{}.tmp", get_app_state_filepath());
+        fs::write(&temp_filepath, serde_json::to_string(&self).unwrap()).expect("write to file failed");
+        fs::rename(&temp_filepath, get_app_state_filepath()).expect("replacing the state file failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone};
+    use serde_json::json;
+
+    use super::AppState;
+    use crate::streak::parse_streak_multipliers;
+
+    fn now() -> DateTime<Local> {
+        return Local.with_ymd_and_hms(2026, 10, 12, 9, 0, 0).unwrap();
+    }
+
+    fn running_task(reward: f64, max_session_minutes: Option<i64>) -> serde_json::Value {
+        return json!({
+            "is_regular": true,
+            "reward_system": { "HourlyTransfer": now() },
+            "reward": reward,
+            "description": "",
+            "name": f!("earning {}", reward),
+            "max_session_minutes": max_session_minutes,
+        });
+    }
+
+    fn rental(cost: f64) -> serde_json::Value {
+        return json!({
+            "name": f!("renting {}", cost),
+            "description": "",
+            "reward_type": { "DecodeFiles": { "files_to_decode": [], "currently_decoded_file_index": null } },
+            "cost": cost,
+            "spending_protocol": { "HourlyTransfer": now() },
+        });
+    }
+
+    fn state(balance: f64, tasks: Vec<serde_json::Value>, rewards: Vec<serde_json::Value>) -> AppState {
+        return serde_json::from_value(json!({
+            "balances": { "points": balance },
+            "tasks": tasks,
+            "rewards": rewards,
+            "folder_pool": [],
+        }))
+        .unwrap();
+    }
+
+    #[test]
+    fn legacy_points_become_the_default_currency() {
+        let mut state: AppState = serde_json::from_value(json!({
+            "cur_points": 7.5,
+            "tasks": [],
+            "rewards": [],
+            "folder_pool": [],
+        }))
+        .unwrap();
+        state.migrate_legacy_points();
+        assert_eq!(state.balance("points"), 7.5);
+        assert_eq!(state.format_balances(), "7.5 points");
+        assert!(serde_json::to_value(&state).unwrap().get("cur_points").is_none());
+    }
+
+    #[test]
+    fn exchange_rules_are_one_way() {
+        let mut state = state(1.0, vec![], vec![]);
+        state.exchange_rates = serde_json::from_value(json!([{ "from": "points", "to": "health", "rate": 2.0 }])).unwrap();
+        assert_eq!(state.exchange_rate("points", "health"), Some(2.0));
+        assert_eq!(state.exchange_rate("health", "points"), None);
+    }
+
+    #[test]
+    fn debt_compounds_once_per_day_passed() {
+        let mut state = state(-100.0, vec![], vec![]);
+        state.balances.insert("health".to_string(), 5.0);
+        state.settings.debt_interest_rate = 0.1;
+        let day = |day: u32| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+        state.accrue_debt_interest_on(day(10));
+        assert_eq!(state.balance("points"), -100.0);
+        state.accrue_debt_interest_on(day(12));
+        state.accrue_debt_interest_on(day(12));
+        assert!((state.balance("points") + 121.0).abs() < 1e-9);
+        assert_eq!(state.balance("health"), 5.0);
+        assert_eq!(state.history.len(), 1);
+        assert!((state.history[0].amount + 21.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn the_debt_ceiling_only_counts_debt() {
+        let mut state = state(-50.0, vec![], vec![]);
+        assert!(!state.is_over_debt_ceiling("points"));
+        state.settings.debt_ceiling = Some(40.0);
+        assert!(state.is_over_debt_ceiling("points"));
+        assert!(!state.is_over_debt_ceiling("health"));
+    }
+
+    #[test]
+    fn rentals_run_the_balance_out() {
+        let state = state(10.0, vec![running_task(1.0, None)], vec![rental(3.0)]);
+        assert_eq!(state.projected_zero_time("points", now()), Some(now() + Duration::hours(5)));
+    }
+
+    #[test]
+    fn capped_sessions_stop_slowing_the_drain() {
+        let state = state(10.0, vec![running_task(1.0, Some(60))], vec![rental(2.0)]);
+        let expected = now() + Duration::hours(5) + Duration::minutes(30);
+        assert_eq!(state.projected_zero_time("points", now()), Some(expected));
+    }
+
+    #[test]
+    fn pausing_does_not_reset_the_session_limit() {
+        let mut task = running_task(1.0, Some(60));
+        task["work_log"] = json!({
+            "sessions": [{ "start": now() - Duration::hours(2), "end": now() - Duration::minutes(80) }],
+        });
+        let state = state(10.0, vec![task], vec![rental(2.0)]);
+        assert_eq!(state.tasks[0].session_cap_time(&state.settings), Some(now() + Duration::minutes(20)));
+        assert_eq!(state.tasks[0].hourly_rate(now() + Duration::minutes(20), &state.settings), 0.0);
+        assert_eq!(state.tasks[0].accrued_reward(now() + Duration::hours(3), &state.settings), 1.0);
+    }
+
+    #[test]
+    fn the_next_streak_multiplier_counts_towards_the_projection() {
+        let mut task = running_task(1.0, None);
+        task["reward_system"] = json!({ "HourlyTransfer": now() - Duration::hours(2) });
+        task["recurrence"] = json!({
+            "rule": "Daily",
+            "completions_per_period": 1,
+            "start_date": "2026-10-01",
+            "completions": [],
+        });
+        task["streak"] = json!({ "current": 2, "best": 2, "last_completion_date": "2026-10-11" });
+        let mut state = state(10.0, vec![task], vec![rental(5.0)]);
+        state.settings.streak_multipliers = parse_streak_multipliers("3:2").unwrap();
+        assert_eq!(state.projected_balance("points", now()), 14.0);
+        assert_eq!(state.hourly_drain("points", now()), 3.0);
+    }
+
+    #[test]
+    fn a_balance_that_is_not_shrinking_never_runs_out() {
+        let earning = state(10.0, vec![running_task(2.0, None)], vec![rental(1.0)]);
+        assert_eq!(earning.projected_zero_time("points", now()), None);
+        let in_debt = state(-1.0, vec![], vec![rental(1.0)]);
+        assert_eq!(in_debt.projected_zero_time("points", now()), None);
+    }
+
+    #[test]
+    fn rates_that_cancel_out_never_run_out() {
+        let cancelling = state(10.0, vec![running_task(0.3, None)], vec![rental(0.1), rental(0.2)]);
+        assert!(cancelling.hourly_drain("points", now()) > 0.0);
+        assert_eq!(cancelling.projected_zero_time("points", now()), None);
+        let centuries_away = state(1e15, vec![], vec![rental(1e-6)]);
+        assert_eq!(centuries_away.projected_zero_time("points", now()), None);
     }
 }
@@ -0,0 +1,83 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::currency::format_amount;
+use crate::task::Task;
+use crate::time_utils::{format_duration, DATE_TIME_FORMAT};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ArchivedTask {
+    pub task: Task,
+    pub completed_at: DateTime<Local>,
+}
+
+impl ArchivedTask {
+    pub fn new(task: Task) -> ArchivedTask {
+        return ArchivedTask {
+            task,
+            completed_at: Local::now(),
+        };
+    }
+
+    // Case-insensitive search through the name, description, project and tags
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let task = &self.task;
+        return task.name.to_lowercase().contains(&query)
+            || task.description.to_lowercase().contains(&query)
+            || task
+                .project
+                .as_ref()
+                .map_or(false, |project| project.to_lowercase().contains(&query))
+            || task.tags.iter().any(|tag| tag.to_lowercase().contains(&query));
+    }
+}
+
+impl Display for ArchivedTask {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} - earned {}",
+            self.completed_at.format(DATE_TIME_FORMAT),
+            self.task.name,
+            format_amount(self.task.points_earned, &self.task.currency)
+        )?;
+        if self.task.logged_seconds > 0 {
+            write!(
+                f,
+                ", {} logged",
+                format_duration(Duration::seconds(self.task.logged_seconds))
+            )?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::ArchivedTask;
+
+    #[test]
+    fn search_looks_at_names_descriptions_projects_and_tags() {
+        let task = serde_json::from_value(json!({
+            "is_regular": false,
+            "reward_system": "SingularTransfer",
+            "reward": 1.0,
+            "description": "Second draft",
+            "name": "Write chapter",
+            "tags": ["Writing"],
+            "project": "Book",
+        }))
+        .unwrap();
+        let archived = ArchivedTask::new(task);
+        assert!(archived.matches("chapter"));
+        assert!(archived.matches("DRAFT"));
+        assert!(archived.matches("book"));
+        assert!(archived.matches("writ"));
+        assert!(!archived.matches("invoice"));
+    }
+}
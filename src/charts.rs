@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::currency::format_amount;
+use crate::history::{HistoryEntry, HistoryKind};
+use crate::time_utils::CalendarPeriod;
+
+// From no completions up to the busiest day in the range
+const HEAT_LEVELS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const BAR_WIDTH: f64 = 40.0;
+// Longer ranges add up several days per glyph
+const MAX_SPARK_WIDTH: i64 = 60;
+// About ten years, longer ranges would not fit a terminal anyway
+pub const MAX_WEEKS: i64 = 520;
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+fn in_range(entry: &HistoryEntry, from: NaiveDate, to: NaiveDate) -> bool {
+    let date = entry.at.date_naive();
+    return from <= date && date <= to;
+}
+
+// Monday of the first heatmap column, every chart of one call starts here
+pub fn charts_start(today: NaiveDate, weeks: i64) -> NaiveDate {
+    return CalendarPeriod::Week.period_start(today) - Duration::weeks(weeks - 1);
+}
+
+// One column per week and one row per weekday, like the contribution graph on GitHub
+pub fn render_heatmap(history: &[HistoryEntry], from: NaiveDate, today: NaiveDate) -> String {
+    let weeks = today.signed_duration_since(from).num_days() / 7 + 1;
+    let mut completions: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    for entry in history
+        .iter()
+        .filter(|entry| entry.completed && in_range(entry, from, today))
+    {
+        *completions.entry(entry.at.date_naive()).or_insert(0) += 1;
+    }
+    let busiest = completions.values().copied().max().unwrap_or(0);
+    let mut lines = vec![f!("Task completions since {} (busiest day: {})", from, busiest)];
+    for (weekday, label) in WEEKDAYS.iter().enumerate() {
+        let mut line = f!("{} ", label);
+        for week in 0..weeks {
+            let date = from + Duration::weeks(week) + Duration::days(weekday as i64);
+            if date > today {
+                break;
+            }
+            let count = *completions.get(&date).unwrap_or(&0);
+            let level = if count == 0 {
+                0
+            } else {
+                ((count as f64 / busiest as f64 * 4.0).ceil() as usize).max(1)
+            };
+            line.push(HEAT_LEVELS[level]);
+            line.push(' ');
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    // Month names go above the first week of each month, as long as they do not run into the previous one
+    let mut months = String::new();
+    for week in 0..weeks {
+        let week_start = from + Duration::weeks(week);
+        let column = week as usize * 2;
+        if (week == 0 || week_start.day() <= 7) && months.chars().count() <= column {
+            months += &" ".repeat(column - months.chars().count());
+            months += &week_start.format("%b").to_string();
+        }
+    }
+    lines.push(f!("    {}", months));
+    return lines.join("\n");
+}
+
+// One line per currency, the lowest day (or group of days) maps to the lowest bar
+pub fn render_sparklines(history: &[HistoryEntry], from: NaiveDate, today: NaiveDate) -> String {
+    let days = today.signed_duration_since(from).num_days() + 1;
+    let days_per_glyph = (days + MAX_SPARK_WIDTH - 1) / MAX_SPARK_WIDTH;
+    let glyphs = ((days + days_per_glyph - 1) / days_per_glyph) as usize;
+    let mut net: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for entry in history.iter().filter(|entry| in_range(entry, from, today)) {
+        let day = entry.at.date_naive().signed_duration_since(from).num_days();
+        net.entry(entry.currency.clone())
+            .or_insert_with(|| vec![0.0; glyphs])[(day / days_per_glyph) as usize] += entry.amount;
+    }
+    if net.is_empty() {
+        return f!("No points were earned or spent since {}", from);
+    }
+    let mut lines = vec![if days_per_glyph == 1 {
+        f!("Daily net points since {}", from)
+    } else {
+        f!("Net points per {} days since {}", days_per_glyph, from)
+    }];
+    for (currency, values) in net {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let spark: String = values
+            .iter()
+            .map(|value| {
+                let level = if max > min {
+                    ((value - min) / (max - min) * 7.0).round() as usize
+                } else {
+                    0
+                };
+                return SPARK_LEVELS[level];
+            })
+            .collect();
+        lines.push(f!(
+            "{} {}  (lowest {}, highest {}, net {})",
+            currency,
+            spark,
+            rounded(min),
+            rounded(max),
+            rounded(values.iter().sum())
+        ));
+    }
+    return lines.join("\n");
+}
+
+pub fn render_spending_bars(history: &[HistoryEntry], from: NaiveDate, to: NaiveDate) -> String {
+    let mut spent: BTreeMap<(String, String), f64> = BTreeMap::new();
+    for entry in history
+        .iter()
+        .filter(|entry| entry.kind == HistoryKind::RewardPurchase && in_range(entry, from, to))
+    {
+        *spent
+            .entry((entry.name.clone(), entry.currency.clone()))
+            .or_insert(0.0) -= entry.amount;
+    }
+    if spent.is_empty() {
+        return f!("Nothing was spent on rewards since {}", from);
+    }
+    let most = spent.values().copied().fold(0.0, f64::max);
+    let name_width = spent.keys().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+    let mut lines = vec![f!("Points spent per reward collection since {}", from)];
+    for ((name, currency), amount) in spent {
+        let bar_length = if most > 0.0 {
+            (amount / most * BAR_WIDTH).round() as usize
+        } else {
+            0
+        };
+        lines.push(f!(
+            "{:<width$} {} {}",
+            name,
+            "█".repeat(bar_length.max(1)),
+            format_amount(rounded(amount), &currency),
+            width = name_width
+        ));
+    }
+    return lines.join("\n");
+}
+
+fn rounded(amount: f64) -> f64 {
+    return (amount * 100.0).round() / 100.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, NaiveDate, TimeZone};
+
+    use super::{charts_start, render_heatmap, render_sparklines, render_spending_bars, MAX_SPARK_WIDTH};
+    use crate::history::{HistoryEntry, HistoryKind};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(2026, month, day).unwrap();
+    }
+
+    fn entry(month: u32, day: u32, kind: HistoryKind, name: &str, amount: f64, completed: bool) -> HistoryEntry {
+        return HistoryEntry {
+            at: Local.with_ymd_and_hms(2026, month, day, 12, 0, 0).unwrap(),
+            kind,
+            name: name.to_string(),
+            currency: "points".to_string(),
+            amount,
+            tags: vec![],
+            project: None,
+            logged_seconds: 0,
+            completed,
+        };
+    }
+
+    #[test]
+    fn charts_start_on_the_monday_of_the_first_week() {
+        assert_eq!(charts_start(date(10, 21), 1), date(10, 19));
+        assert_eq!(charts_start(date(10, 21), 3), date(10, 5));
+    }
+
+    #[test]
+    fn the_heatmap_counts_finished_tasks_only() {
+        let history = vec![
+            entry(10, 19, HistoryKind::TaskPayout, "write", 1.0, true),
+            entry(10, 19, HistoryKind::TaskPayout, "write", 1.0, true),
+            entry(10, 20, HistoryKind::TaskPayout, "checklist item", 1.0, false),
+            entry(10, 20, HistoryKind::TaskPayout, "read", 1.0, true),
+        ];
+        let heatmap = render_heatmap(&history, date(10, 19), date(10, 21));
+        let lines: Vec<&str> = heatmap.lines().collect();
+        assert_eq!(lines[0], "Task completions since 2026-10-19 (busiest day: 2)");
+        assert_eq!(lines[1], "Mon █");
+        assert_eq!(lines[2], "Tue ▒");
+        assert_eq!(lines[3], "Wed ·");
+        assert_eq!(lines[4], "Thu");
+    }
+
+    #[test]
+    fn long_sparklines_group_days() {
+        let history = vec![
+            entry(10, 5, HistoryKind::TaskPayout, "write", 3.0, true),
+            entry(10, 6, HistoryKind::RewardPurchase, "movies", -1.0, false),
+        ];
+        let short = render_sparklines(&history, date(10, 5), date(10, 11));
+        assert_eq!(short.lines().next().unwrap(), "Daily net points since 2026-10-05");
+        assert!(short.lines().nth(1).unwrap().starts_with("points █▁▃▃▃▃▃ "));
+        let long_from = charts_start(date(10, 11), 520);
+        let long = render_sparklines(&history, long_from, date(10, 11));
+        assert!(long.lines().next().unwrap().starts_with("Net points per 61 days since"));
+        let spark = long.lines().nth(1).unwrap().split(' ').nth(1).unwrap();
+        assert!(spark.chars().count() as i64 <= MAX_SPARK_WIDTH);
+    }
+
+    #[test]
+    fn spending_bars_scale_to_the_biggest_spender() {
+        let history = vec![
+            entry(10, 5, HistoryKind::RewardPurchase, "movies", -4.0, false),
+            entry(10, 6, HistoryKind::RewardPurchase, "games", -1.0, false),
+            entry(10, 6, HistoryKind::TaskPayout, "write", 10.0, true),
+        ];
+        let bars = render_spending_bars(&history, date(10, 5), date(10, 11));
+        let lines: Vec<&str> = bars.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], f!("games  {} 1 points", "█".repeat(10)));
+        assert_eq!(lines[2], f!("movies {} 4 points", "█".repeat(40)));
+    }
+}
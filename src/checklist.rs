@@ -0,0 +1,97 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli_utils::{get_line, get_parsed_line_with_condition};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChecklistItem {
+    pub name: String,
+    // Share of the task reward paid when the item is checked off, 0.2 is a fifth
+    pub reward_share: f64,
+    pub done: bool,
+}
+
+pub fn init_checklist_from_cli() -> Option<Vec<ChecklistItem>> {
+    let mut checklist: Vec<ChecklistItem> = vec![];
+    loop {
+        let name = get_line(Some(
+            "Enter the name of the next checklist item (empty to finish): ",
+        ))?;
+        if name.trim().is_empty() {
+            return Some(checklist);
+        }
+        let shares_left = 1.0 - total_share(&checklist);
+        let reward_share: f64 = get_parsed_line_with_condition(
+            Some(&f!(
+                "Enter the share of the reward for this item ({} left, the rest is paid when all items are done): ",
+                shares_left
+            )),
+            |float_val: &f64| *float_val >= 0.0 && *float_val <= shares_left + f64::EPSILON,
+        )?;
+        checklist.push(ChecklistItem {
+            name: name.trim().to_string(),
+            reward_share,
+            done: false,
+        });
+    }
+}
+
+pub fn total_share(checklist: &[ChecklistItem]) -> f64 {
+    return checklist.iter().map(|item| item.reward_share).sum();
+}
+
+// Share of the reward left for completing the whole checklist
+pub fn completion_share(checklist: &[ChecklistItem]) -> f64 {
+    return (1.0 - total_share(checklist)).max(0.0);
+}
+
+pub fn done_count(checklist: &[ChecklistItem]) -> usize {
+    return checklist.iter().filter(|item| item.done).count();
+}
+
+pub fn choose_item(checklist: &[ChecklistItem]) -> Option<usize> {
+    for (i, item) in checklist.iter().enumerate() {
+        println!("{}.\t{}", i + 1, item);
+    }
+    let item_number: usize = get_parsed_line_with_condition(
+        Some("Enter index of checklist item to check off: "),
+        |int_val: &usize| *int_val > 0 && *int_val <= checklist.len() && !checklist[*int_val - 1].done,
+    )?;
+    return Some(item_number - 1);
+}
+
+impl Display for ChecklistItem {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        return write!(
+            f,
+            "[{}] {} ({}%)",
+            if self.done { "x" } else { " " },
+            self.name,
+            self.reward_share * 100.0
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{completion_share, done_count, total_share, ChecklistItem};
+
+    fn item(name: &str, reward_share: f64, done: bool) -> ChecklistItem {
+        return ChecklistItem {
+            name: name.to_string(),
+            reward_share,
+            done,
+        };
+    }
+
+    #[test]
+    fn the_rest_of_the_reward_is_paid_on_completion() {
+        let checklist = vec![item("draft", 0.25, true), item("edit", 0.5, false)];
+        assert_eq!(total_share(&checklist), 0.75);
+        assert_eq!(completion_share(&checklist), 0.25);
+        assert_eq!(done_count(&checklist), 1);
+        let overshared = vec![item("draft", 0.75, false), item("edit", 0.5, false)];
+        assert_eq!(completion_share(&overshared), 0.0);
+    }
+}
@@ -1,45 +1,124 @@
-pub fn get_line(prompt: Option<&str>) -> String {
-    match prompt {
-        Some(prompt_str) => println!("{}", prompt_str),
-        _ => (),
-    };
-    let mut input = String::new();
-    std::io::stdin()
-        .read_line(&mut input)
-        .expect("Console reading failed");
-    return input.strip_suffix('\n').unwrap().to_string();
-}
-
-pub fn get_parsed_line<T: std::str::FromStr>(prompt: Option<&str>) -> T {
-    return match get_line(prompt).parse::<T>() {
-        Ok(val) => val,
-        Err(er) => get_parsed_line(prompt),
-    };
-}
-
-pub fn get_line_with_condition<F>(prompt: Option<&str>, condition: F) -> String
+use std::cell::RefCell;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+// Typed at any prompt to abandon what is being entered
+pub const CANCEL_INPUT: &str = "!q";
+
+static INPUT_CLOSED: AtomicBool = AtomicBool::new(false);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+const INTERRUPT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+thread_local! {
+    // Lines read by the stdin thread, only set up once Ctrl-C is handled
+    static INPUT_LINES: RefCell<Option<Receiver<io::Result<String>>>> = const { RefCell::new(None) };
+}
+
+// Set once stdin hits EOF (Ctrl-D), every later prompt is cancelled right away
+pub fn is_input_closed() -> bool {
+    return INPUT_CLOSED.load(Ordering::SeqCst);
+}
+
+pub fn is_interrupted() -> bool {
+    return INTERRUPTED.load(Ordering::SeqCst);
+}
+
+pub fn print_interrupted() {
+    println!("\nInterrupted, changes up to the last finished action are saved");
+}
+
+// Editing loops check for the interrupt after every action, then save and return.
+// Stdin moves to its own thread so a waiting prompt can give up and let the loop unwind.
+pub fn stop_on_interrupt() {
+    ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst)).expect("Could not set the Ctrl-C handler");
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || loop {
+        let mut input = String::new();
+        let read_result = io::stdin().read_line(&mut input).map(|_| input);
+        let is_last = !matches!(&read_result, Ok(input) if !input.is_empty());
+        if sender.send(read_result).is_err() || is_last {
+            return;
+        }
+    });
+    INPUT_LINES.with(|lines| *lines.borrow_mut() = Some(receiver));
+}
+
+// Returns None when interrupted, an empty string means the input is closed
+fn read_input() -> Option<String> {
+    return INPUT_LINES.with(|lines| {
+        let lines = lines.borrow();
+        let receiver = match lines.as_ref() {
+            Some(receiver) => receiver,
+            None => {
+                let mut input = String::new();
+                io::stdin().read_line(&mut input).expect("Console reading failed");
+                return Some(input);
+            }
+        };
+        loop {
+            if is_interrupted() {
+                return None;
+            }
+            match receiver.recv_timeout(INTERRUPT_CHECK_INTERVAL) {
+                Ok(read_result) => return Some(read_result.expect("Console reading failed")),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return Some(String::new()),
+            }
+        }
+    });
+}
+
+// Returns None when the user cancels or the input is closed
+pub fn get_line(prompt: Option<&str>) -> Option<String> {
+    if is_input_closed() || is_interrupted() {
+        return None;
+    }
+    if let Some(prompt_str) = prompt {
+        println!("{}", prompt_str);
+    }
+    let input = read_input()?;
+    if input.is_empty() {
+        INPUT_CLOSED.store(true, Ordering::SeqCst);
+        return None;
+    }
+    let line = input.trim_end_matches(&['\r', '\n'][..]).to_string();
+    if line.trim() == CANCEL_INPUT {
+        return None;
+    }
+    return Some(line);
+}
+
+pub fn get_parsed_line<T: std::str::FromStr>(prompt: Option<&str>) -> Option<T> {
+    return get_parsed_line_with_condition(prompt, |_| true);
+}
+
+pub fn get_line_with_condition<F>(prompt: Option<&str>, condition: F) -> Option<String>
 where
     F: Fn(&String) -> bool,
 {
-    let line = get_line(prompt);
-    return match condition(&line) {
-        true => line,
-        false => get_line_with_condition(prompt, condition),
-    };
+    loop {
+        let line = get_line(prompt)?;
+        if condition(&line) {
+            return Some(line);
+        }
+    }
 }
 
 pub fn get_parsed_line_with_condition<T: std::str::FromStr, F>(
     prompt: Option<&str>,
     condition: F,
-) -> T
+) -> Option<T>
 where
     F: Fn(&T) -> bool,
 {
-    return match get_line(prompt).parse::<T>() {
-        Ok(val) => match condition(&val) {
-            true => val,
-            false => get_parsed_line_with_condition(prompt, condition),
-        },
-        Err(er) => get_parsed_line_with_condition(prompt, condition),
-    };
+    loop {
+        if let Ok(val) = get_line(prompt)?.parse::<T>() {
+            if condition(&val) {
+                return Some(val);
+            }
+        }
+    }
 }
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cli_utils::get_line;
+
+pub fn default_currency() -> String {
+    return "points".to_string();
+}
+
+pub fn get_currency_from_cli() -> Option<String> {
+    let currency = get_line(Some("Enter the currency (empty for points): "))?;
+    return Some(if currency.trim().is_empty() {
+        default_currency()
+    } else {
+        currency.trim().to_string()
+    });
+}
+
+pub fn format_amount(amount: f64, currency: &str) -> String {
+    return format!("{} {}", amount, currency);
+}
+
+// One-way rule: `amount` of `from` can be turned into `amount * rate` of `to`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExchangeRate {
+    pub from: String,
+    pub to: String,
+    pub rate: f64,
+}
@@ -0,0 +1,506 @@
+use std::io;
+use std::panic;
+use std::time::Duration as StdDuration;
+
+use chrono::Local;
+use crossterm::cursor::Show;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use tui::backend::{Backend, CrosstermBackend};
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use tui::{Frame, Terminal};
+
+use crate::app_state::AppState;
+use crate::cli_utils::get_line;
+use crate::currency::format_amount;
+use crate::reward_collection::{RewardCollection, RewardType};
+use crate::task::{RewardPointTransferProtocol, Task};
+use crate::time_utils::format_duration;
+
+type DashboardTerminal = Terminal<CrosstermBackend<io::Stdout>>;
+
+// Timers are redrawn at least this often even without key presses
+const REFRESH_INTERVAL: StdDuration = StdDuration::from_secs(1);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Tasks,
+    Rewards,
+}
+
+enum Mode {
+    Browse,
+    ConfirmRemove,
+    // Index of the reward whose files are being picked from
+    PickFile(usize),
+}
+
+struct Dashboard {
+    focus: Pane,
+    tasks: ListState,
+    rewards: ListState,
+    files: ListState,
+    mode: Mode,
+    message: String,
+}
+
+impl Dashboard {
+    fn new() -> Dashboard {
+        return Dashboard {
+            focus: Pane::Tasks,
+            tasks: ListState::default(),
+            rewards: ListState::default(),
+            files: ListState::default(),
+            mode: Mode::Browse,
+            message: String::new(),
+        };
+    }
+
+    fn focused_list(&mut self) -> &mut ListState {
+        return match self.focus {
+            Pane::Tasks => &mut self.tasks,
+            Pane::Rewards => &mut self.rewards,
+        };
+    }
+
+    fn focused_len(&self, state: &AppState) -> usize {
+        return match self.focus {
+            Pane::Tasks => state.tasks.len(),
+            Pane::Rewards => state.rewards.len(),
+        };
+    }
+
+    fn selected(&self) -> Option<usize> {
+        return match self.focus {
+            Pane::Tasks => self.tasks.selected(),
+            Pane::Rewards => self.rewards.selected(),
+        };
+    }
+
+    // Keeps the selections inside the lists after entries were added or removed
+    fn clamp_selections(&mut self, state: &AppState) {
+        clamp_selection(&mut self.tasks, state.tasks.len());
+        clamp_selection(&mut self.rewards, state.rewards.len());
+    }
+}
+
+fn clamp_selection(list: &mut ListState, len: usize) {
+    let selected = match (list.selected(), len) {
+        (_, 0) => None,
+        (Some(index), len) => Some(index.min(len - 1)),
+        (None, _) => Some(0),
+    };
+    list.select(selected);
+}
+
+fn move_selection(list: &mut ListState, len: usize, forward: bool) {
+    if len == 0 {
+        return;
+    }
+    let selected = list.selected().unwrap_or(0);
+    list.select(Some(if forward {
+        (selected + 1) % len
+    } else {
+        (selected + len - 1) % len
+    }));
+}
+
+pub fn run_dashboard() {
+    let mut state = AppState::load_from_disk();
+    state.rewards.iter_mut().for_each(|reward| reward.validate());
+    state.save_on_disk();
+    restore_terminal_on_panic();
+    let mut terminal = match enter_screen().and_then(|_| Terminal::new(CrosstermBackend::new(io::stdout()))) {
+        Ok(terminal) => terminal,
+        Err(err) => {
+            let _ = disable_raw_mode();
+            println!("Could not open the dashboard: {}", err);
+            return;
+        }
+    };
+    let result = dashboard_loop(&mut terminal, &mut state);
+    let _ = leave_screen(&mut terminal);
+    let _ = panic::take_hook();
+    state.save_on_disk();
+    if let Err(err) = result {
+        println!("The dashboard stopped because of a terminal error: {}", err);
+    }
+}
+
+// Without this a panic leaves the shell in raw mode on the alternate screen
+fn restore_terminal_on_panic() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+        default_hook(info);
+    }));
+}
+
+fn enter_screen() -> io::Result<()> {
+    enable_raw_mode()?;
+    return execute!(io::stdout(), EnterAlternateScreen);
+}
+
+fn leave_screen(terminal: &mut DashboardTerminal) -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    return terminal.show_cursor();
+}
+
+// Prompts and messages of the regular commands need a plain terminal, so the dashboard steps aside
+fn run_outside<F: FnOnce()>(terminal: &mut DashboardTerminal, action: F) -> io::Result<()> {
+    leave_screen(terminal)?;
+    action();
+    get_line(Some("Press Enter to return to the dashboard"));
+    enter_screen()?;
+    return terminal.clear();
+}
+
+fn dashboard_loop(terminal: &mut DashboardTerminal, state: &mut AppState) -> io::Result<()> {
+    let mut dashboard = Dashboard::new();
+    loop {
+        dashboard.clamp_selections(state);
+        state.sample_work_processes();
+        terminal.draw(|frame| draw(frame, state, &mut dashboard))?;
+        if !event::poll(REFRESH_INTERVAL)? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if !handle_key(key, terminal, state, &mut dashboard)? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+// Returns false once the user quits
+fn handle_key(
+    key: KeyEvent,
+    terminal: &mut DashboardTerminal,
+    state: &mut AppState,
+    dashboard: &mut Dashboard,
+) -> io::Result<bool> {
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        return Ok(false);
+    }
+    match dashboard.mode {
+        Mode::ConfirmRemove => {
+            if key.code == KeyCode::Char('y') {
+                remove_selected(state, dashboard);
+            } else {
+                dashboard.message = "Nothing removed".to_string();
+            }
+            dashboard.mode = Mode::Browse;
+        }
+        Mode::PickFile(reward_index) => match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                move_selection(&mut dashboard.files, file_count(&state.rewards[reward_index]), false)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                move_selection(&mut dashboard.files, file_count(&state.rewards[reward_index]), true)
+            }
+            KeyCode::Enter => {
+                let chosen_file = dashboard.files.selected();
+                dashboard.mode = Mode::Browse;
+                run_outside(terminal, || crate::tick_reward_at(state, reward_index, chosen_file))?;
+                state.save_on_disk();
+            }
+            KeyCode::Esc | KeyCode::Char('q') => dashboard.mode = Mode::Browse,
+            _ => (),
+        },
+        Mode::Browse => {
+            dashboard.message.clear();
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
+                    dashboard.focus = match dashboard.focus {
+                        Pane::Tasks => Pane::Rewards,
+                        Pane::Rewards => Pane::Tasks,
+                    }
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let len = dashboard.focused_len(state);
+                    move_selection(dashboard.focused_list(), len, false);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let len = dashboard.focused_len(state);
+                    move_selection(dashboard.focused_list(), len, true);
+                }
+                KeyCode::Char('t') | KeyCode::Enter => tick_selected(terminal, state, dashboard)?,
+                KeyCode::Char('a') => {
+                    run_outside(terminal, || {
+                        let added = match dashboard.focus {
+                            Pane::Tasks => crate::add_task(state),
+                            Pane::Rewards => crate::add_reward(state),
+                        };
+                        if added.is_none() {
+                            println!("Cancelled");
+                        }
+                    })?;
+                    state.save_on_disk();
+                }
+                KeyCode::Char('e') => {
+                    if let Some(index) = dashboard.selected() {
+                        run_outside(terminal, || match dashboard.focus {
+                            Pane::Tasks => crate::edit_task_at(state, index),
+                            Pane::Rewards => state.rewards[index].edit_from_cli(),
+                        })?;
+                        state.save_on_disk();
+                    }
+                }
+                KeyCode::Char('p') => {
+                    if let (Pane::Tasks, Some(index)) = (dashboard.focus, dashboard.selected()) {
+                        run_outside(terminal, || crate::pause_or_resume_task_at(state, index))?;
+                        state.save_on_disk();
+                    }
+                }
+                KeyCode::Char('d') | KeyCode::Delete if dashboard.selected().is_some() => {
+                    dashboard.mode = Mode::ConfirmRemove;
+                }
+                _ => (),
+            }
+        }
+    }
+    return Ok(true);
+}
+
+fn tick_selected(terminal: &mut DashboardTerminal, state: &mut AppState, dashboard: &mut Dashboard) -> io::Result<()> {
+    let index = match dashboard.selected() {
+        Some(index) => index,
+        None => return Ok(()),
+    };
+    if dashboard.focus == Pane::Rewards && needs_file_picker(&state.rewards[index]) {
+        dashboard.files.select(Some(0));
+        dashboard.mode = Mode::PickFile(index);
+        return Ok(());
+    }
+    run_outside(terminal, || match dashboard.focus {
+        Pane::Tasks => crate::tick_task_at(state, index),
+        Pane::Rewards => crate::tick_reward_at(state, index, None),
+    })?;
+    state.save_on_disk();
+    return Ok(());
+}
+
+// Returning a rental or rolling a mystery file needs no choice from the user
+fn needs_file_picker(reward: &RewardCollection) -> bool {
+    return match &reward.reward_type {
+        RewardType::DecodeFiles(decode_files) => {
+            reward.rented_since().is_none()
+                && decode_files.is_chosen_by_user()
+                && !decode_files.files_to_decode.is_empty()
+        }
+    };
+}
+
+fn file_count(reward: &RewardCollection) -> usize {
+    return match &reward.reward_type {
+        RewardType::DecodeFiles(decode_files) => decode_files.files_to_decode.len(),
+    };
+}
+
+fn remove_selected(state: &mut AppState, dashboard: &mut Dashboard) {
+    let index = match dashboard.selected() {
+        Some(index) => index,
+        None => return,
+    };
+    let removed_name = match dashboard.focus {
+        Pane::Tasks => state.remove_task(index).name,
+        Pane::Rewards => state.rewards.remove(index).name,
+    };
+    state.save_on_disk();
+    dashboard.message = f!("Removed {}", removed_name);
+}
+
+fn draw<B: Backend>(frame: &mut Frame<B>, state: &AppState, dashboard: &mut Dashboard) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(6), Constraint::Length(8), Constraint::Length(1)])
+        .split(frame.size());
+    let lists = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(rows[0]);
+    let summaries = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(rows[1]);
+
+    let task_items: Vec<ListItem> = state
+        .tasks
+        .iter()
+        .map(|task| ListItem::new(task_line(task, state)))
+        .collect();
+    let task_list = List::new(task_items)
+        .block(pane_block("Tasks", dashboard.focus == Pane::Tasks))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(task_list, lists[0], &mut dashboard.tasks);
+
+    let reward_items: Vec<ListItem> = state.rewards.iter().map(|reward| ListItem::new(reward_line(reward))).collect();
+    let reward_list = List::new(reward_items)
+        .block(pane_block("Rewards", dashboard.focus == Pane::Rewards))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(reward_list, lists[1], &mut dashboard.rewards);
+
+    frame.render_widget(
+        Paragraph::new(balance_lines(state)).block(Block::default().borders(Borders::ALL).title("Balance")),
+        summaries[0],
+    );
+    frame.render_widget(
+        Paragraph::new(timer_lines(state)).block(Block::default().borders(Borders::ALL).title("Running timers")),
+        summaries[1],
+    );
+    frame.render_widget(Paragraph::new(help_line(state, dashboard)), rows[2]);
+
+    if let Mode::PickFile(reward_index) = dashboard.mode {
+        let RewardType::DecodeFiles(decode_files) = &state.rewards[reward_index].reward_type;
+        let area = centered_rect(frame.size(), 70, 60);
+        let file_items: Vec<ListItem> = decode_files
+            .files_to_decode
+            .iter()
+            .map(|file| ListItem::new(file.to_string()))
+            .collect();
+        let file_list = List::new(file_items)
+            .block(pane_block(&f!("Files of {}", state.rewards[reward_index].name), true))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(file_list, area, &mut dashboard.files);
+    }
+}
+
+fn pane_block(title: &str, focused: bool) -> Block<'static> {
+    let border_style = if focused {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    return Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_style)
+        .title(title.to_string());
+}
+
+fn task_line(task: &Task, state: &AppState) -> String {
+    let mut line = task.summary_line();
+    if let Some(start) = task.running_since() {
+        line += &f!(" [running {}]", format_duration(Local::now().signed_duration_since(start)));
+    } else if task.is_paused() {
+        line += " [paused]";
+    }
+    if !task.remaining_prerequisites(&state.tasks).is_empty() {
+        line += " [blocked]";
+    }
+    return line;
+}
+
+fn reward_line(reward: &RewardCollection) -> String {
+    let cost_kind = match reward.spending_protocol {
+        RewardPointTransferProtocol::SingularTransfer => "one-time",
+        RewardPointTransferProtocol::HourlyTransfer(_) => "per hour",
+    };
+    let mut line = f!(
+        "{} - {} {}, {} file(s)",
+        reward.name,
+        format_amount(reward.cost, &reward.currency),
+        cost_kind,
+        file_count(reward)
+    );
+    if let Some(start) = reward.rented_since() {
+        line += &f!(" [rented {}]", format_duration(Local::now().signed_duration_since(start)));
+    }
+    return line;
+}
+
+fn balance_lines(state: &AppState) -> Vec<Spans<'static>> {
+    let mut lines: Vec<Spans> = state
+        .balances
+        .iter()
+        .map(|(currency, amount)| {
+            let style = if *amount < 0.0 {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            Spans::from(Span::styled(format_amount(*amount, currency), style))
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Spans::from(format_amount(0.0, &crate::currency::default_currency())));
+    }
+    lines.push(Spans::from(f!(
+        "Streak: {} day(s), best {}",
+        state.completion_streak.active_streak(Local::now().date_naive()),
+        state.completion_streak.best
+    )));
+    return lines;
+}
+
+fn timer_lines(state: &AppState) -> Vec<Spans<'static>> {
+    let now = Local::now();
+    let mut lines = vec![];
+    for task in &state.tasks {
+        if let Some(start) = task.running_since() {
+            lines.push(Spans::from(Span::styled(
+                f!(
+                    "{}: {} worked, +{} so far",
+                    task.name,
+                    format_duration(now.signed_duration_since(start)),
+                    format_amount(task.accrued_reward(now, &state.settings), &task.currency)
+                ),
+                Style::default().fg(Color::Green),
+            )));
+        }
+    }
+    for reward in &state.rewards {
+        if let Some(start) = reward.rented_since() {
+            lines.push(Spans::from(Span::styled(
+                f!(
+                    "{}: rented for {}, -{} so far",
+                    reward.name,
+                    format_duration(now.signed_duration_since(start)),
+                    format_amount(reward.accrued_cost(now), &reward.currency)
+                ),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+    if lines.is_empty() {
+        lines.push(Spans::from("No timers running"));
+    }
+    return lines;
+}
+
+fn help_line(state: &AppState, dashboard: &Dashboard) -> String {
+    return match dashboard.mode {
+        Mode::ConfirmRemove => {
+            let name = match (dashboard.focus, dashboard.selected()) {
+                (Pane::Tasks, Some(index)) => state.tasks[index].name.clone(),
+                (Pane::Rewards, Some(index)) => state.rewards[index].name.clone(),
+                (_, None) => String::new(),
+            };
+            f!("Remove {}? y - yes, any other key - no", name)
+        }
+        Mode::PickFile(_) => "Up/Down - move  Enter - decode the file  Esc - cancel".to_string(),
+        Mode::Browse if !dashboard.message.is_empty() => dashboard.message.clone(),
+        Mode::Browse => {
+            "Tab - switch pane  Up/Down - move  t - tick  a - add  e - edit  d - remove  p - pause/resume  q - quit"
+                .to_string()
+        }
+    };
+}
+
+fn centered_rect(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = area.height * percent_y / 100;
+    return Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+}
@@ -0,0 +1,146 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::cli_utils::{get_line_with_condition, get_parsed_line_with_condition};
+use crate::settings::Settings;
+use crate::time_utils::{format_duration, parse_date_time, DATE_TIME_FORMAT};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Deadline {
+    pub due: DateTime<Local>,
+    // Deducted once, the first time the tool starts after the deadline has passed
+    pub missed_penalty: Option<f64>,
+    pub penalty_applied: bool,
+    // Set when the task is completed before the deadline, a met deadline is never penalised
+    #[serde(default)]
+    pub met: bool,
+}
+
+impl Deadline {
+    // The outer None means the input was cancelled, the inner one that there is no deadline
+    pub fn init_deadline_from_cli() -> Option<Option<Deadline>> {
+        let line = get_line_with_condition(
+            Some("Enter the deadline (YYYY-MM-DD HH:MM, empty for none): "),
+            |line| line.trim().is_empty() || parse_date_time(line).is_some(),
+        )?;
+        let due = match parse_date_time(&line) {
+            Some(due) => due,
+            None => return Some(None),
+        };
+        let missed_penalty: f64 = get_parsed_line_with_condition(
+            Some("Enter the amount deducted if the deadline is missed (0 for none): "),
+            |float_val: &f64| *float_val >= 0.0,
+        )?;
+        return Some(Some(Deadline {
+            due,
+            missed_penalty: if missed_penalty > 0.0 { Some(missed_penalty) } else { None },
+            penalty_applied: false,
+            met: false,
+        }));
+    }
+
+    // Late payouts halve every `late_half_life_hours`, early ones grow linearly up to a cap
+    pub fn payout_multiplier(&self, now: DateTime<Local>, settings: &Settings) -> f64 {
+        let hours_left = self.due.signed_duration_since(now).num_minutes() as f64 / 60.0;
+        return if hours_left < 0.0 {
+            0.5_f64.powf(-hours_left / settings.late_half_life_hours)
+        } else {
+            1.0 + (settings.early_bonus_per_day * hours_left / 24.0).min(settings.max_early_bonus)
+        };
+    }
+
+    pub fn is_missed(&self, now: DateTime<Local>) -> bool {
+        return now > self.due;
+    }
+
+    pub fn mark_met(&mut self, now: DateTime<Local>) {
+        if !self.is_missed(now) {
+            self.met = true;
+        }
+    }
+
+    // Returns the penalty if it is due now, marking it as applied
+    pub fn take_pending_penalty(&mut self, now: DateTime<Local>) -> Option<f64> {
+        if self.met || self.penalty_applied || !self.is_missed(now) {
+            return None;
+        }
+        self.penalty_applied = true;
+        return self.missed_penalty;
+    }
+}
+
+impl Display for Deadline {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let now = Local::now();
+        let time_description = if self.met {
+            "met".to_string()
+        } else if self.is_missed(now) {
+            format!("missed {} ago", format_duration(now.signed_duration_since(self.due)))
+        } else {
+            format!("in {}", format_duration(self.due.signed_duration_since(now)))
+        };
+        write!(f, "{} ({})", self.due.format(DATE_TIME_FORMAT), time_description)?;
+        if let (Some(penalty), false) = (self.missed_penalty, self.met) {
+            write!(
+                f,
+                ", {} penalty {}",
+                penalty,
+                if self.penalty_applied { "applied" } else { "if missed" }
+            )?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Local, TimeZone};
+
+    use super::Deadline;
+    use crate::settings::Settings;
+
+    fn due() -> DateTime<Local> {
+        return Local.with_ymd_and_hms(2026, 10, 12, 18, 0, 0).unwrap();
+    }
+
+    fn deadline(missed_penalty: Option<f64>) -> Deadline {
+        return Deadline {
+            due: due(),
+            missed_penalty,
+            penalty_applied: false,
+            met: false,
+        };
+    }
+
+    #[test]
+    fn late_payouts_halve_and_early_ones_grow_up_to_the_cap() {
+        let settings = Settings::default();
+        let deadline = deadline(None);
+        assert_eq!(deadline.payout_multiplier(due() + Duration::hours(24), &settings), 0.5);
+        assert_eq!(deadline.payout_multiplier(due() + Duration::hours(48), &settings), 0.25);
+        assert_eq!(deadline.payout_multiplier(due(), &settings), 1.0);
+        assert!((deadline.payout_multiplier(due() - Duration::days(2), &settings) - 1.04).abs() < 1e-9);
+        assert_eq!(deadline.payout_multiplier(due() - Duration::days(30), &settings), 1.1);
+    }
+
+    #[test]
+    fn the_penalty_is_taken_once_after_the_deadline() {
+        let mut deadline = deadline(Some(5.0));
+        assert_eq!(deadline.take_pending_penalty(due()), None);
+        assert_eq!(deadline.take_pending_penalty(due() + Duration::minutes(1)), Some(5.0));
+        assert_eq!(deadline.take_pending_penalty(due() + Duration::hours(1)), None);
+    }
+
+    #[test]
+    fn met_deadlines_are_never_penalised() {
+        let mut met = deadline(Some(5.0));
+        met.mark_met(due() - Duration::hours(1));
+        assert_eq!(met.take_pending_penalty(due() + Duration::days(1)), None);
+        let mut late = deadline(Some(5.0));
+        late.mark_met(due() + Duration::hours(1));
+        assert!(!late.met);
+        assert_eq!(late.take_pending_penalty(due() + Duration::days(1)), Some(5.0));
+    }
+}
@@ -0,0 +1,365 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::app_state::AppState;
+use crate::history::HistoryKind;
+use crate::reward_collection::{FileSelectionMode, RewardType};
+use crate::task::{RewardPointTransferProtocol, Task};
+
+// Columns are only ever appended to, so spreadsheets built on earlier exports keep working
+#[derive(Serialize, Default)]
+struct TaskRow {
+    name: String,
+    description: String,
+    project: String,
+    tags: String,
+    priority: String,
+    reward: f64,
+    currency: String,
+    reward_system: &'static str,
+    is_regular: bool,
+    deadline: String,
+    running_since: String,
+    points_earned: f64,
+    logged_seconds: i64,
+    // Empty for tasks that are still open
+    completed_at: String,
+}
+
+#[derive(Serialize, Default)]
+struct RewardRow {
+    name: String,
+    description: String,
+    cost: f64,
+    currency: String,
+    spending_protocol: &'static str,
+    selection_mode: &'static str,
+    file_count: usize,
+    rented_since: String,
+}
+
+// Which hidden file holds which reward, one row per encoded file
+#[derive(Serialize, Default)]
+struct RewardFileRow {
+    collection: String,
+    reward_name: String,
+    path_before_encoding: String,
+    path_after_encoding: String,
+    rarity: String,
+    is_decoded: bool,
+}
+
+#[derive(Serialize, Default)]
+struct HistoryRow {
+    at: String,
+    kind: &'static str,
+    name: String,
+    currency: String,
+    amount: f64,
+    tags: String,
+    project: String,
+    logged_seconds: i64,
+    completed: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        return match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        };
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ExportDataset {
+    Tasks,
+    Rewards,
+    RewardFiles,
+    History,
+}
+
+impl ExportDataset {
+    pub const ALL: [ExportDataset; 4] = [
+        ExportDataset::Tasks,
+        ExportDataset::Rewards,
+        ExportDataset::RewardFiles,
+        ExportDataset::History,
+    ];
+
+    fn file_stem(&self) -> &'static str {
+        return match self {
+            ExportDataset::Tasks => "tasks",
+            ExportDataset::Rewards => "rewards",
+            ExportDataset::RewardFiles => "reward_files",
+            ExportDataset::History => "history",
+        };
+    }
+}
+
+pub struct ExportOptions {
+    pub datasets: Vec<ExportDataset>,
+    pub format: ExportFormat,
+    pub directory: PathBuf,
+}
+
+impl ExportOptions {
+    // Exports everything as CSV into the current directory unless told otherwise
+    pub fn from_args(args: &[String]) -> Result<ExportOptions, String> {
+        let mut options = ExportOptions {
+            datasets: vec![],
+            format: ExportFormat::Csv,
+            directory: PathBuf::from("."),
+        };
+        let mut remaining_args = args.iter();
+        while let Some(arg) = remaining_args.next() {
+            let dataset = match arg.as_str() {
+                "tasks" => Some(ExportDataset::Tasks),
+                "rewards" => Some(ExportDataset::Rewards),
+                "files" => Some(ExportDataset::RewardFiles),
+                "history" => Some(ExportDataset::History),
+                _ => None,
+            };
+            if let Some(dataset) = dataset {
+                if !options.datasets.contains(&dataset) {
+                    options.datasets.push(dataset);
+                }
+                continue;
+            }
+            let value = remaining_args.next().ok_or(f!("Missing value for {}", arg))?;
+            match arg.as_str() {
+                "--format" => {
+                    options.format = match value.as_str() {
+                        "csv" => ExportFormat::Csv,
+                        "json" => ExportFormat::Json,
+                        _ => return Err(f!("{} is not csv or json", value)),
+                    }
+                }
+                "--dir" => options.directory = PathBuf::from(value),
+                _ => return Err(f!("Unknown option {}", arg)),
+            }
+        }
+        if options.datasets.is_empty() {
+            options.datasets = ExportDataset::ALL.to_vec();
+        }
+        return Ok(options);
+    }
+}
+
+// RFC 3339 keeps the seconds and the UTC offset, so events stay in order across DST changes
+fn format_time(time: Option<DateTime<Local>>) -> String {
+    return time.map_or(String::new(), |time| time.to_rfc3339());
+}
+
+fn protocol_name(protocol: &RewardPointTransferProtocol) -> &'static str {
+    return match protocol {
+        RewardPointTransferProtocol::HourlyTransfer(_) => "hourly",
+        RewardPointTransferProtocol::SingularTransfer => "one-time",
+    };
+}
+
+fn task_row(task: &Task, completed_at: Option<DateTime<Local>>) -> TaskRow {
+    return TaskRow {
+        name: task.name.clone(),
+        description: task.description.clone(),
+        project: task.project.clone().unwrap_or_default(),
+        tags: task.tags.join(";"),
+        priority: task.priority.to_string(),
+        reward: task.reward,
+        currency: task.currency.clone(),
+        reward_system: protocol_name(&task.reward_system),
+        is_regular: task.is_regular,
+        deadline: format_time(task.deadline.as_ref().map(|deadline| deadline.due)),
+        running_since: format_time(task.running_since()),
+        points_earned: task.points_earned,
+        logged_seconds: task.logged_seconds,
+        completed_at: format_time(completed_at),
+    };
+}
+
+fn task_rows(state: &AppState) -> Vec<TaskRow> {
+    let open_tasks = state.tasks.iter().map(|task| task_row(task, None));
+    let archived_tasks = state
+        .archive
+        .iter()
+        .map(|archived| task_row(&archived.task, Some(archived.completed_at)));
+    return open_tasks.chain(archived_tasks).collect();
+}
+
+fn reward_rows(state: &AppState) -> Vec<RewardRow> {
+    return state
+        .rewards
+        .iter()
+        .map(|reward| {
+            let RewardType::DecodeFiles(decode_files) = &reward.reward_type;
+            return RewardRow {
+                name: reward.name.clone(),
+                description: reward.description.clone(),
+                cost: reward.cost,
+                currency: reward.currency.clone(),
+                spending_protocol: protocol_name(&reward.spending_protocol),
+                selection_mode: match decode_files.selection_mode {
+                    FileSelectionMode::Chosen => "chosen",
+                    FileSelectionMode::Mystery(_) => "mystery",
+                },
+                file_count: decode_files.files_to_decode.len(),
+                rented_since: format_time(reward.rented_since()),
+            };
+        })
+        .collect();
+}
+
+fn reward_file_rows(state: &AppState) -> Vec<RewardFileRow> {
+    let mut rows = vec![];
+    for reward in &state.rewards {
+        let RewardType::DecodeFiles(decode_files) = &reward.reward_type;
+        for (i, file) in decode_files.files_to_decode.iter().enumerate() {
+            rows.push(RewardFileRow {
+                collection: reward.name.clone(),
+                reward_name: file.reward_name.clone(),
+                path_before_encoding: file.path_before_encoding.clone(),
+                path_after_encoding: file.path_after_encoding.clone(),
+                rarity: file.rarity.clone().unwrap_or_default(),
+                is_decoded: decode_files.currently_decoded_file_index == Some(i),
+            });
+        }
+    }
+    return rows;
+}
+
+fn history_rows(state: &AppState) -> Vec<HistoryRow> {
+    return state
+        .history
+        .iter()
+        .map(|entry| HistoryRow {
+            at: format_time(Some(entry.at)),
+            kind: match entry.kind {
+                HistoryKind::TaskPayout => "task payout",
+                HistoryKind::RewardPurchase => "reward purchase",
+                HistoryKind::DeadlinePenalty => "deadline penalty",
+                HistoryKind::DebtInterest => "debt interest",
+                HistoryKind::Exchange => "exchange",
+            },
+            name: entry.name.clone(),
+            currency: entry.currency.clone(),
+            amount: entry.amount,
+            tags: entry.tags.join(";"),
+            project: entry.project.clone().unwrap_or_default(),
+            logged_seconds: entry.logged_seconds,
+            completed: entry.completed,
+        })
+        .collect();
+}
+
+fn to_csv<T: Serialize>(rows: &[T]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for row in rows {
+        writer.serialize(row).map_err(|err| err.to_string())?;
+    }
+    return String::from_utf8(writer.into_inner().map_err(|err| err.to_string())?).map_err(|err| err.to_string());
+}
+
+fn write_rows<T: Serialize + Default>(path: &Path, rows: &[T], format: ExportFormat) -> Result<(), String> {
+    let contents = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(rows).map_err(|err| err.to_string())?,
+        // The header comes from the first row, an empty export borrows it from a blank one
+        ExportFormat::Csv if rows.is_empty() => {
+            let blank = to_csv(&[T::default()])?;
+            blank.lines().next().unwrap_or_default().to_string() + "\n"
+        }
+        ExportFormat::Csv => to_csv(rows)?,
+    };
+    return fs::write(path, contents).map_err(|err| f!("Could not write {}: {}", path.display(), err));
+}
+
+// Returns the paths of the written files
+pub fn export(state: &AppState, options: &ExportOptions) -> Result<Vec<PathBuf>, String> {
+    fs::create_dir_all(&options.directory)
+        .map_err(|err| f!("Could not create {}: {}", options.directory.display(), err))?;
+    let mut written = vec![];
+    for dataset in &options.datasets {
+        let path = options
+            .directory
+            .join(f!("{}.{}", dataset.file_stem(), options.format.extension()));
+        match dataset {
+            ExportDataset::Tasks => write_rows(&path, &task_rows(state), options.format)?,
+            ExportDataset::Rewards => write_rows(&path, &reward_rows(state), options.format)?,
+            ExportDataset::RewardFiles => write_rows(&path, &reward_file_rows(state), options.format)?,
+            ExportDataset::History => write_rows(&path, &history_rows(state), options.format)?,
+        }
+        written.push(path);
+    }
+    return Ok(written);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::{Local, TimeZone};
+
+    use super::{
+        format_time, to_csv, ExportDataset, ExportFormat, ExportOptions, HistoryRow, RewardFileRow, RewardRow,
+        TaskRow,
+    };
+
+    fn args(values: &[&str]) -> Vec<String> {
+        return values.iter().map(|value| value.to_string()).collect();
+    }
+
+    fn header<T: serde::Serialize + Default>() -> String {
+        return to_csv(&[T::default()]).unwrap().lines().next().unwrap().to_string();
+    }
+
+    #[test]
+    fn every_dataset_is_exported_unless_some_are_named() {
+        let all = ExportOptions::from_args(&args(&[])).unwrap();
+        assert!(all.datasets == ExportDataset::ALL.to_vec());
+        assert!(all.format == ExportFormat::Csv);
+        assert_eq!(all.directory, PathBuf::from("."));
+        let named = ExportOptions::from_args(&args(&["history", "tasks", "history", "--format", "json"])).unwrap();
+        assert!(named.datasets == vec![ExportDataset::History, ExportDataset::Tasks]);
+        assert!(named.format == ExportFormat::Json);
+        assert!(ExportOptions::from_args(&args(&["--format", "xml"])).is_err());
+        assert!(ExportOptions::from_args(&args(&["--dir"])).is_err());
+        assert!(ExportOptions::from_args(&args(&["notes"])).is_err());
+    }
+
+    // Spreadsheets built on earlier exports rely on these, new columns only ever go at the end
+    #[test]
+    fn csv_columns_stay_stable() {
+        assert_eq!(
+            header::<TaskRow>(),
+            "name,description,project,tags,priority,reward,currency,reward_system,is_regular,deadline,running_since,points_earned,logged_seconds,completed_at"
+        );
+        assert_eq!(
+            header::<RewardRow>(),
+            "name,description,cost,currency,spending_protocol,selection_mode,file_count,rented_since"
+        );
+        assert_eq!(
+            header::<RewardFileRow>(),
+            "collection,reward_name,path_before_encoding,path_after_encoding,rarity,is_decoded"
+        );
+        assert_eq!(
+            header::<HistoryRow>(),
+            "at,kind,name,currency,amount,tags,project,logged_seconds,completed"
+        );
+    }
+
+    #[test]
+    fn times_keep_seconds_and_the_offset() {
+        let time = Local.with_ymd_and_hms(2026, 10, 12, 9, 30, 15).unwrap();
+        assert_eq!(format_time(Some(time)), time.to_rfc3339());
+        assert!(format_time(Some(time)).starts_with("2026-10-12T09:30:15"));
+        assert_eq!(format_time(None), "");
+    }
+}
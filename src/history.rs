@@ -0,0 +1,92 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::reward_collection::RewardCollection;
+use crate::task::Task;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryKind {
+    TaskPayout,
+    RewardPurchase,
+    DeadlinePenalty,
+    DebtInterest,
+    // One entry on each side, the `name` is the other currency
+    Exchange,
+}
+
+// One balance change, with what the task looked like at that moment so later edits do not rewrite the past
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub at: DateTime<Local>,
+    pub kind: HistoryKind,
+    pub name: String,
+    pub currency: String,
+    // Positive for payouts, negative for purchases and penalties
+    pub amount: f64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub project: Option<String>,
+    // Work time the event closed, for hourly tasks
+    #[serde(default)]
+    pub logged_seconds: i64,
+    // Set when a payout finished the task, partial payouts of checklists leave it unset
+    #[serde(default)]
+    pub completed: bool,
+}
+
+impl HistoryEntry {
+    pub fn task_payout(task: &Task, amount: f64, logged_seconds: i64, completed: bool) -> HistoryEntry {
+        let mut entry = HistoryEntry::for_task(task, HistoryKind::TaskPayout, amount, logged_seconds);
+        entry.completed = completed;
+        return entry;
+    }
+
+    pub fn deadline_penalty(task: &Task, penalty: f64) -> HistoryEntry {
+        return HistoryEntry::for_task(task, HistoryKind::DeadlinePenalty, -penalty, 0);
+    }
+
+    pub fn reward_purchase(reward: &RewardCollection, points_spent: f64) -> HistoryEntry {
+        return HistoryEntry::for_balance(HistoryKind::RewardPurchase, &reward.name, &reward.currency, -points_spent);
+    }
+
+    pub fn debt_interest(currency: &str, interest: f64) -> HistoryEntry {
+        return HistoryEntry::for_balance(HistoryKind::DebtInterest, "debt interest", currency, -interest);
+    }
+
+    // The outgoing and the incoming side of an exchange
+    pub fn exchange(from: &str, to: &str, amount: f64, received: f64) -> [HistoryEntry; 2] {
+        return [
+            HistoryEntry::for_balance(HistoryKind::Exchange, to, from, -amount),
+            HistoryEntry::for_balance(HistoryKind::Exchange, from, to, received),
+        ];
+    }
+
+    fn for_balance(kind: HistoryKind, name: &str, currency: &str, amount: f64) -> HistoryEntry {
+        return HistoryEntry {
+            at: Local::now(),
+            kind,
+            name: name.to_string(),
+            currency: currency.to_string(),
+            amount,
+            tags: vec![],
+            project: None,
+            logged_seconds: 0,
+            completed: false,
+        };
+    }
+
+    fn for_task(task: &Task, kind: HistoryKind, amount: f64, logged_seconds: i64) -> HistoryEntry {
+        return HistoryEntry {
+            at: Local::now(),
+            kind,
+            name: task.name.clone(),
+            currency: task.currency.clone(),
+            amount,
+            tags: task.tags.clone(),
+            project: task.project.clone(),
+            logged_seconds,
+            completed: false,
+        };
+    }
+}
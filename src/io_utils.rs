@@ -17,10 +17,10 @@ pub fn generate_name() -> String {
     return name;
 }
 
-pub fn encode_file_by_moving_initial(folder_pool: &Vec<String>, path: &String) -> String {
+pub fn encode_file_by_moving_initial(folder_pool: &[String], path: &String) -> String {
     let mut rng = rand::thread_rng();
-    let new_path = (&folder_pool[rng.gen_range(0..folder_pool.len())]).clone() + &generate_name();
-    fs::rename(&path, &new_path).expect(&f!("Couldn't move file {} to {}!", &path, &new_path));
+    let new_path = folder_pool[rng.gen_range(0..folder_pool.len())].clone() + &generate_name();
+    fs::rename(path, &new_path).expect(&f!("Couldn't move file {} to {}!", &path, &new_path));
     return new_path;
 }
 pub fn encode_file_by_moving(chosen_path: &String, current_path: &String) {
@@ -38,8 +38,8 @@ pub fn decode_file_from_moving(current_path: &String, initial_path: &String) {
     ));
 }
 
-pub fn encode_file_by_compression(key: &Key, path: &String) {
-    let mut file_bytes = fs::read(path).expect(&f!("Could not read file {} bytewise!", &path));
+pub fn encode_file_by_compression(_key: &Key, path: &String) {
+    let file_bytes = fs::read(path).expect(&f!("Could not read file {} bytewise!", &path));
 
     // unneeded since compressing/decompressing data is sufficient for now
     // encrypt(key, &mut file_bytes);
@@ -50,11 +50,11 @@ pub fn encode_file_by_compression(key: &Key, path: &String) {
     io::copy(&mut file_bytes.as_slice(), &mut encoder).unwrap();
     encoder.finish().unwrap();
 
-    fs::write(&path, encoded_file_bytes).expect(&f!("Could not write to file {} bytewise!", &path));
+    fs::write(path, encoded_file_bytes).expect(&f!("Could not write to file {} bytewise!", &path));
 }
 
-pub fn decode_file_from_compression(key: &Key, path: &String) {
-    let mut file_bytes = fs::read(&path).expect(&f!("Could not read file {} bytewise!", &path));
+pub fn decode_file_from_compression(_key: &Key, path: &String) {
+    let file_bytes = fs::read(path).expect(&f!("Could not read file {} bytewise!", &path));
 
     let mut decoded_file_bytes = Vec::new();
     let mut binding = file_bytes.as_slice();
@@ -66,7 +66,7 @@ pub fn decode_file_from_compression(key: &Key, path: &String) {
     // unneeded since compressing/decompressing data is sufficient for now
     // decrypt(key, &mut decoded_file_bytes);
 
-    fs::write(&path, decoded_file_bytes).expect(&f!("Could not write to file {} bytewise!", &path));
+    fs::write(path, decoded_file_bytes).expect(&f!("Could not write to file {} bytewise!", &path));
 }
 
 pub fn encode_file_unstable(key: &Key, path: &String) {
@@ -85,16 +85,16 @@ pub fn encode_file_unstable(key: &Key, path: &String) {
 pub fn decode_file_unstable(key: &Key, path: &String) {
     let mut file_bytes = extract_from(path).expect(&f!("couldn't extract from archive {}", path));
 
-    fs::remove_file(&path).expect(&f!(
+    fs::remove_file(path).expect(&f!(
         "couldn't remove archive at {} after extracting the data",
         &path
     ));
 
     decrypt(key, &mut file_bytes);
-    fs::write(&path, file_bytes).expect(&f!("Could not write to file {} bytewise!", &path));
+    fs::write(path, file_bytes).expect(&f!("Could not write to file {} bytewise!", &path));
 }
 
-pub fn create_archive(bytes_to_write: &Vec<u8>, archive_path: &String) -> ZipResult<()> {
+pub fn create_archive(bytes_to_write: &[u8], archive_path: &String) -> ZipResult<()> {
     let path = std::path::Path::new(archive_path);
     let file = std::fs::File::create(path).unwrap();
 
@@ -104,7 +104,7 @@ pub fn create_archive(bytes_to_write: &Vec<u8>, archive_path: &String) -> ZipRes
         .compression_method(zip::CompressionMethod::Zstd)
         .unix_permissions(0o755);
     zip.start_file("main", options)?;
-    zip.write(bytes_to_write)?;
+    zip.write_all(bytes_to_write)?;
 
     zip.finish()?;
     Ok(())
@@ -112,7 +112,7 @@ pub fn create_archive(bytes_to_write: &Vec<u8>, archive_path: &String) -> ZipRes
 pub fn extract_from(archive_path: &String) -> ZipResult<Vec<u8>> {
     // Open archive and extract the file
     let path = std::path::Path::new(&archive_path);
-    let file = fs::File::open(&path).unwrap();
+    let file = fs::File::open(path).unwrap();
     let mut archive = zip::ZipArchive::new(file).unwrap();
     let mut file = archive.by_index(0)?; //.expect("INVALID PASSWORD!");
 
@@ -1,37 +1,74 @@
+#![allow(clippy::needless_return)]
+
 pub mod app_state;
+pub mod archive;
+pub mod charts;
+pub mod checklist;
 pub mod cli_utils;
 pub mod crypto_utils;
+pub mod currency;
+pub mod dashboard;
+pub mod deadline;
+pub mod export;
+pub mod history;
 pub mod io_utils;
+pub mod recurrence;
+pub mod report;
 pub mod reward_collection;
+pub mod selection;
+pub mod settings;
+pub mod status;
+pub mod streak;
 pub mod task;
+pub mod task_view;
+pub mod template;
+pub mod time_utils;
+pub mod work_log;
 
 #[macro_use]
 extern crate fstrings;
 
 use app_state::AppState;
+use archive::ArchivedTask;
+use history::HistoryEntry;
 use reward_collection::RewardCollection;
-use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
 use std::io;
-use std::iter::Filter;
-use std::path;
 use std::path::Path;
-use sysinfo::{ProcessExt, SystemExt};
+use sysinfo::SystemExt;
 
-use crate::cli_utils::get_parsed_line_with_condition;
-use crate::crypto_utils::make_key;
-use crate::reward_collection::DecodeFilesReward;
+use crate::cli_utils::{
+    get_line_with_condition, get_parsed_line_with_condition, is_input_closed, is_interrupted,
+    print_interrupted, stop_on_interrupt, CANCEL_INPUT,
+};
 use crate::reward_collection::RewardType;
 use crate::reward_collection::SingularFileToDecode;
+use crate::selection::{resolve_key, select_from_cli};
+use crate::currency::{format_amount, ExchangeRate};
+use crate::export::ExportOptions;
+use crate::report::{Report, ReportFormat, ReportOptions};
+use crate::task::RewardPointTransferProtocol;
 use crate::task::Task;
+use crate::task_view::TaskView;
+use crate::template::TaskTemplate;
+use crate::time_utils::{format_duration, DATE_TIME_FORMAT};
+use chrono::{Duration, Local};
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    // Status bars call this every few seconds, it must stay quiet and never touch the state file
+    if args.get(1).map(String::as_str) == Some("status") {
+        print_status(&args);
+        return;
+    }
+
     if !Path::new(app_state::get_app_state_filepath()).exists() {
         app_state::initialize_default_app_state();
     }
 
-    let args: Vec<String> = env::args().collect();
-    let arguments_description = "\tte - opens in task editing mode, with task ticking and such.\n\tef - encodes file for reward in the given reward collection (default name is the file name)\n\t\tArg1: \"*path to file or directory to be encoded*\" \n\t\tArg2: \"*reward collection*\" \n\t\tArg3: \"*reward name (optional)*\"\n\tre - opens in reward editing mode, where you can buy/edit rewards";
+    settle_pending_charges();
+
+    let arguments_description = "\tte - opens in task editing mode, with task ticking and such.\n\tui - opens a full-screen dashboard with tasks, rewards, the balance and running timers\n\tef - encodes file for reward in the given reward collection (default name is the file name)\n\t\tArg1: \"*path to file or directory to be encoded*\" \n\t\tArg2: \"*reward collection*\" \n\t\tArg3: \"*reward name (optional)*\"\n\t\tArg4: \"*rarity tier for mystery collections (optional)*\"\n\tre - opens in reward editing mode, where you can buy/edit rewards\n\ttasks - lists tasks one per line\n\t\t--tag \"*tag*\" \n\t\t--project \"*project*\" \n\t\t--priority \"*minimal priority*\" \n\t\t--sort \"*index, priority, name, project or deadline*\"\n\ttask new - adds a task\n\t\t--from \"*template name (optional)*\"\n\ttask tick - ticks a task\n\t\tArg1: \"*task index, name, name prefix or fuzzy match*\"\n\ttask remove - removes a task\n\t\tArg1: \"*task index, name, name prefix or fuzzy match*\"\n\ttask template list - lists task templates\n\ttask template save - saves an existing task as a template\n\t\tArg1: \"*task index or name*\" \n\t\tArg2: \"*template name*\"\n\ttask template remove - removes a task template\n\t\tArg1: \"*template name*\"\n\treward tick - buys a reward, or starts/stops renting it\n\t\tArg1: \"*reward index, name, name prefix or fuzzy match*\"\n\treward remove - removes a reward\n\t\tArg1: \"*reward index, name, name prefix or fuzzy match*\"\n\tstatus - prints one line with the balance and running timers, for status bars\n\t\t--format \"*template with {balance}, {tasks} and {rewards} (optional)*\"\n\tar - lists completed tasks from the archive\n\t\tArg1: \"search\" or \"restore\" (optional) \n\t\tArg2: \"*search query*\" or \"*archive index to re-create the task from*\"\n\trp - shows which tasks would pay off the debt fastest\n\tex - shows balances and exchange rates, or exchanges currencies\n\t\tArg1: \"*amount to exchange*\" or \"rate\" \n\t\tArg2: \"*currency to exchange from*\" \n\t\tArg3: \"*currency to exchange to*\" \n\t\tArg4: \"*new rate, when setting one (0 removes the rule)*\"\n\tcf - shows settings, or changes one of them\n\t\tArg1: \"*setting name (optional)*\" \n\t\tArg2: \"*new value (optional)*\"\n\treport - sums up points earned per task, tag and project and spent per reward collection (today by default)\n\t\tArg1: \"day\", \"week\" or \"month\" (optional) \n\t\t--from \"*YYYY-MM-DD*\" \n\t\t--to \"*YYYY-MM-DD*\" \n\t\t--format \"*table or json*\"\n\tcharts - draws a heatmap of task completions, daily net points and spending per reward collection\n\t\t--weeks \"*how many weeks back to show (default 12)*\"\n\texport - writes tasks, reward collections, encoded file mappings and point history to files (all of them by default)\n\t\tArg1: \"tasks\", \"rewards\", \"files\" or \"history\" (optional) \n\t\t--format \"*csv or json (default csv)*\" \n\t\t--dir \"*directory to write to (default current)*\"";
     println!("{:?}", args);
     if args.len() <= 1 {
         println!("No command line arguments!\n{}", arguments_description);
@@ -39,15 +76,44 @@ fn main() {
     }
     match args[1].as_str() {
         "te" => task_editing_loop(),
+        "ui" => dashboard::run_dashboard(),
         "ef" => start_reward_addition(args),
         "re" => {
             validate_rewards();
             reward_editing_loop();
         }
+        "tasks" => list_tasks(args),
+        "task" => manage_task(args),
+        "reward" => {
+            validate_rewards();
+            manage_reward(args);
+        }
+        "ar" => browse_archive(args),
+        "rp" => show_repayment_plan(),
+        "ex" => exchange_currencies(args),
+        "cf" => change_settings(args),
+        "report" => print_report(args),
+        "charts" => print_charts(args),
+        "export" => export_data(args),
         _ => println!("Incorrect arguments!\n{}", arguments_description),
     }
 }
 
+fn print_status(args: &[String]) {
+    let state = match AppState::read_from_disk() {
+        Some(state) => state,
+        None => {
+            println!("no state");
+            return;
+        }
+    };
+    let template = match (args.get(2).map(String::as_str), args.get(3)) {
+        (Some("--format"), Some(template)) => template.clone(),
+        _ => state.settings.status_template.clone(),
+    };
+    println!("{}", status::format_status(&state, &template, chrono::Local::now()));
+}
+
 fn validate_rewards() {
     let mut state = app_state::AppState::load_from_disk();
     state
@@ -57,22 +123,451 @@ fn validate_rewards() {
     state.save_on_disk();
 }
 
+fn settle_pending_charges() {
+    let mut state = AppState::load_from_disk();
+    state.accrue_debt_interest();
+    apply_missed_deadline_penalties(&mut state);
+    state.save_on_disk();
+}
+
+fn apply_missed_deadline_penalties(state: &mut AppState) {
+    let now = chrono::Local::now();
+    let mut penalties = vec![];
+    for task in state.tasks.iter_mut() {
+        if let Some(penalty) = task.deadline.as_mut().and_then(|deadline| deadline.take_pending_penalty(now)) {
+            println!(
+                "Missed the deadline of {}: {} deducted",
+                task.name,
+                format_amount(penalty, &task.currency)
+            );
+            penalties.push((task.currency.clone(), penalty, HistoryEntry::deadline_penalty(task, penalty)));
+        }
+    }
+    for (currency, penalty, entry) in penalties {
+        state.add_points(&currency, -penalty);
+        state.history.push(entry);
+    }
+}
+
+fn manage_task(args: Vec<String>) {
+    let mut state = AppState::load_from_disk();
+    let command: Vec<&str> = args[2..].iter().map(String::as_str).collect();
+    match command.as_slice() {
+        ["new"] => {
+            let new_task = match Task::init_task_from_cli() {
+                Some(new_task) => new_task,
+                None => return,
+            };
+            push_new_task(&mut state, new_task);
+        }
+        ["new", "--from", template_name] => {
+            let template = match state.templates.iter().find(|template| template.name == *template_name) {
+                Some(template) => template,
+                None => {
+                    println!("There is no template named {}!", template_name);
+                    return;
+                }
+            };
+            let new_task = match template.instantiate_from_cli() {
+                Some(new_task) => new_task,
+                None => return,
+            };
+            push_new_task(&mut state, new_task);
+        }
+        ["template", "list"] => {
+            for template in &state.templates {
+                println!("{}", template);
+            }
+            return;
+        }
+        ["tick", task_key] => {
+            let index = match find_task_index(&state, task_key) {
+                Some(index) => index,
+                None => return,
+            };
+            tick_task_at(&mut state, index);
+        }
+        ["remove", task_key] => {
+            let index = match find_task_index(&state, task_key) {
+                Some(index) => index,
+                None => return,
+            };
+            println!("Removed task {}", state.remove_task(index).name);
+        }
+        ["template", "save", task_key, template_name] => {
+            let task = match find_task_index(&state, task_key) {
+                Some(index) => &state.tasks[index],
+                None => return,
+            };
+            let template = TaskTemplate::from_task(template_name.to_string(), task);
+            state.templates.retain(|existing| existing.name != *template_name);
+            state.templates.push(template);
+            println!("Saved template {}", template_name);
+        }
+        ["template", "remove", template_name] => {
+            state.templates.retain(|existing| existing.name != *template_name);
+        }
+        _ => {
+            println!("Unknown task command!");
+            return;
+        }
+    }
+    state.save_on_disk();
+}
+
+fn manage_reward(args: Vec<String>) {
+    let mut state = AppState::load_from_disk();
+    let command: Vec<&str> = args[2..].iter().map(String::as_str).collect();
+    match command.as_slice() {
+        ["tick", reward_key] => {
+            let index = match find_reward_index(&state, reward_key) {
+                Some(index) => index,
+                None => return,
+            };
+            tick_reward_at(&mut state, index, None);
+        }
+        ["remove", reward_key] => {
+            let index = match find_reward_index(&state, reward_key) {
+                Some(index) => index,
+                None => return,
+            };
+            println!("Removed reward {}", state.rewards.remove(index).name);
+        }
+        _ => {
+            println!("Unknown reward command!");
+            return;
+        }
+    }
+    state.save_on_disk();
+}
+
+// Accepts a 1-based index, a name, a unique prefix or a fuzzy match of the task
+fn find_task_index(state: &AppState, key: &str) -> Option<usize> {
+    let names: Vec<&str> = state.tasks.iter().map(|task| task.name.as_str()).collect();
+    return resolve_key(&names, key);
+}
+
+fn find_reward_index(state: &AppState, key: &str) -> Option<usize> {
+    let names: Vec<&str> = state.rewards.iter().map(|reward| reward.name.as_str()).collect();
+    return resolve_key(&names, key);
+}
+
+fn select_task_from_cli(state: &AppState, action: &str) -> Option<usize> {
+    let names: Vec<&str> = state.tasks.iter().map(|task| task.name.as_str()).collect();
+    return select_from_cli(&f!("Enter index or name of task to {}: ", action), &names);
+}
+
+fn select_reward_from_cli(state: &AppState, action: &str) -> Option<usize> {
+    let names: Vec<&str> = state.rewards.iter().map(|reward| reward.name.as_str()).collect();
+    return select_from_cli(&f!("Enter index or name of reward to {}: ", action), &names);
+}
+
+fn browse_archive(args: Vec<String>) {
+    let mut state = AppState::load_from_disk();
+    match (args.get(2).map(String::as_str), args.get(3)) {
+        (Some("search"), Some(query)) => {
+            for (i, archived_task) in state.archive.iter().enumerate() {
+                if archived_task.matches(query) {
+                    println!("{}. {}", i + 1, archived_task);
+                }
+            }
+        }
+        (Some("restore"), Some(index)) => {
+            let position = index.parse::<usize>().ok().and_then(|index| index.checked_sub(1));
+            let archived_task = match position.and_then(|position| state.archive.get(position)) {
+                Some(archived_task) => archived_task,
+                None => {
+                    println!("There is no archived task #{}", index);
+                    return;
+                }
+            };
+            let task = archived_task.task.fresh_copy();
+            println!("Re-created task {}", task.name);
+            state.tasks.push(task);
+            state.save_on_disk();
+        }
+        (None, _) => {
+            for (i, archived_task) in state.archive.iter().enumerate() {
+                println!("{}. {}", i + 1, archived_task);
+            }
+        }
+        _ => println!("Unknown archive command!"),
+    }
+}
+
+fn print_report(args: Vec<String>) {
+    let options = match ReportOptions::from_args(&args[2..]) {
+        Ok(options) => options,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    let state = AppState::load_from_disk();
+    let report = Report::build(&state.history, options.from, options.to);
+    match options.format {
+        ReportFormat::Table => println!("{}", report),
+        ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+    }
+}
+
+fn print_charts(args: Vec<String>) {
+    let weeks: i64 = match (args.get(2).map(String::as_str), args.get(3)) {
+        (None, _) => 12,
+        (Some("--weeks"), Some(weeks)) => match weeks.parse::<i64>() {
+            Ok(weeks) if weeks > 0 => {
+                if weeks > charts::MAX_WEEKS {
+                    println!("Showing the last {} weeks, the most the charts cover", charts::MAX_WEEKS);
+                }
+                weeks.min(charts::MAX_WEEKS)
+            }
+            _ => {
+                println!("{} is not a positive number of weeks", weeks);
+                return;
+            }
+        },
+        _ => {
+            println!("Unknown charts options!");
+            return;
+        }
+    };
+    let state = AppState::load_from_disk();
+    let today = Local::now().date_naive();
+    let from = charts::charts_start(today, weeks);
+    println!("{}\n", charts::render_heatmap(&state.history, from, today));
+    println!("{}\n", charts::render_sparklines(&state.history, from, today));
+    println!("{}", charts::render_spending_bars(&state.history, from, today));
+}
+
+fn export_data(args: Vec<String>) {
+    let options = match ExportOptions::from_args(&args[2..]) {
+        Ok(options) => options,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    let state = AppState::load_from_disk();
+    match export::export(&state, &options) {
+        Ok(written) => {
+            for path in written {
+                println!("Exported {}", path.display());
+            }
+        }
+        Err(err) => println!("{}", err),
+    }
+}
+
+fn show_repayment_plan() {
+    let state = AppState::load_from_disk();
+    let debts: Vec<(&String, f64)> = state
+        .balances
+        .iter()
+        .filter(|(_, amount)| **amount < 0.0)
+        .map(|(currency, amount)| (currency, -*amount))
+        .collect();
+    if debts.is_empty() {
+        println!("No debt to repay! Balance: {}", state.format_balances());
+        return;
+    }
+    for (currency, debt) in debts {
+        print_repayment_plan(&state, currency, debt);
+    }
+}
+
+fn print_repayment_plan(state: &AppState, currency: &str, debt: f64) {
+    println!("Debt: {}", format_amount(debt, currency));
+    let (mut hourly_tasks, mut completion_tasks): (Vec<&Task>, Vec<&Task>) = state
+        .tasks
+        .iter()
+        .filter(|task| task.reward.is_finite() && task.reward > 0.0 && task.currency == currency)
+        .partition(|task| matches!(task.reward_system, RewardPointTransferProtocol::HourlyTransfer(_)));
+    // Hourly rates and one-off amounts are different units, so each kind is ranked on its own
+    for tasks in [&mut hourly_tasks, &mut completion_tasks] {
+        tasks.sort_by(|a, b| b.reward.partial_cmp(&a.reward).unwrap_or(std::cmp::Ordering::Equal));
+    }
+    if !hourly_tasks.is_empty() {
+        println!("Per-hour tasks, by the work time needed:");
+    }
+    for task in hourly_tasks {
+        let minutes = (debt / task.reward * 60.0).ceil() as i64;
+        println!("{}: {} of work", task.name, format_duration(Duration::minutes(minutes)));
+    }
+    if !completion_tasks.is_empty() {
+        println!("Per-completion tasks, by the completions needed:");
+    }
+    for task in completion_tasks {
+        let repayment = if task.is_regular {
+            f!("{} completion(s)", (debt / task.reward).ceil())
+        } else if task.reward >= debt {
+            "one completion".to_string()
+        } else {
+            f!("covers {} of the debt (one-off)", task.reward)
+        };
+        println!("{}: {}", task.name, repayment);
+    }
+}
+
+fn exchange_currencies(args: Vec<String>) {
+    let mut state = AppState::load_from_disk();
+    match (args.get(2).map(String::as_str), args.get(3), args.get(4)) {
+        (Some(_), Some(from), Some(to)) if from == to => {
+            println!("Can not exchange {} for itself!", from);
+            return;
+        }
+        (Some("rate"), Some(from), Some(to)) => {
+            let rate: f64 = match args.get(5).map(|rate| rate.parse()) {
+                Some(Ok(rate)) => rate,
+                _ => {
+                    println!("The rate should be a number!");
+                    return;
+                }
+            };
+            state
+                .exchange_rates
+                .retain(|existing| !(&existing.from == from && &existing.to == to));
+            if rate.is_finite() && rate > 0.0 {
+                state.exchange_rates.push(ExchangeRate {
+                    from: from.clone(),
+                    to: to.clone(),
+                    rate,
+                });
+            }
+            state.save_on_disk();
+        }
+        (Some(amount), Some(from), Some(to)) => {
+            let amount: f64 = match amount.parse() {
+                Ok(amount) => amount,
+                Err(_) => {
+                    println!("The amount should be a number!");
+                    return;
+                }
+            };
+            let rate = match state.exchange_rate(from, to) {
+                Some(rate) => rate,
+                None => {
+                    println!("There is no exchange rule from {} to {}!", from, to);
+                    return;
+                }
+            };
+            if !(amount.is_finite() && amount > 0.0) || state.balance(from) < amount {
+                println!("Can only exchange a positive amount that is covered by the balance!");
+                return;
+            }
+            state.add_points(from, -amount);
+            state.add_points(to, amount * rate);
+            state
+                .history
+                .extend(HistoryEntry::exchange(from, to, amount, amount * rate));
+            println!(
+                "Exchanged {} for {}",
+                format_amount(amount, from),
+                format_amount(amount * rate, to)
+            );
+            state.save_on_disk();
+        }
+        _ => (),
+    }
+    println!("Balance: {}", state.format_balances());
+    for rate in &state.exchange_rates {
+        println!("1 {} -> {} {}", rate.from, rate.rate, rate.to);
+    }
+}
+
+fn change_settings(args: Vec<String>) {
+    let mut state = AppState::load_from_disk();
+    if let (Some(key), Some(value)) = (args.get(2), args.get(3)) {
+        if let Err(err) = state.settings.set(key, value) {
+            println!("{}", err);
+            return;
+        }
+        state.save_on_disk();
+    }
+    println!("{}", state.settings);
+}
+
 fn reward_editing_loop() {
+    stop_on_interrupt();
     let mut state = app_state::AppState::load_from_disk();
     loop {
         println!("\n\n\n");
-        println!("Points: {}", state.cur_points);
-        edit_rewards(&mut state);
+        state.sample_work_processes();
+        print_points(&state);
+        let keep_editing = edit_rewards(&mut state);
         state.save_on_disk();
+        if is_interrupted() {
+            print_interrupted();
+            return;
+        }
+        if !keep_editing || is_input_closed() {
+            return;
+        }
+    }
+}
+fn print_points(state: &AppState) {
+    let in_debt = state.balances.values().any(|amount| *amount < 0.0);
+    if in_debt && state.settings.debt_interest_rate > 0.0 {
+        println!(
+            "Balance: {} (in debt, {}% daily interest)",
+            state.format_balances(),
+            state.settings.debt_interest_rate * 100.0
+        );
+    } else {
+        println!("Balance: {}", state.format_balances());
+    }
+    print_projections(state);
+}
+
+// What the balance would be once running tasks and rentals are settled, and when rentals would use it up
+fn print_projections(state: &AppState) {
+    let now = Local::now();
+    for currency in state.currencies_with_timers() {
+        let mut line = f!(
+            "Projected with running timers: {}",
+            format_amount(state.projected_balance(&currency, now), &currency)
+        );
+        if let Some(zero_time) = state.projected_zero_time(&currency, now) {
+            line += &f!(
+                ", runs out at {} (in {})",
+                zero_time.format(DATE_TIME_FORMAT),
+                format_duration(zero_time.signed_duration_since(now))
+            );
+        }
+        println!("{}", line);
     }
 }
+
 fn task_editing_loop() {
+    stop_on_interrupt();
     let mut state = app_state::AppState::load_from_disk();
+    let mut view = TaskView::default();
     loop {
         println!("\n\n\n");
-        println!("Points: {}", state.cur_points);
-        edit_tasks(&mut state);
+        state.sample_work_processes();
+        print_points(&state);
+        let keep_editing = edit_tasks(&mut state, &mut view);
         state.save_on_disk();
+        if is_interrupted() {
+            print_interrupted();
+            return;
+        }
+        if !keep_editing || is_input_closed() {
+            return;
+        }
+    }
+}
+
+fn list_tasks(args: Vec<String>) {
+    let view = match TaskView::from_args(&args[2..]) {
+        Ok(view) => view,
+        Err(err) => {
+            println!("{}", err);
+            return;
+        }
+    };
+    let state = AppState::load_from_disk();
+    for (i, task) in view.apply(&state.tasks) {
+        println!("{}. {}", i + 1, task.summary_line());
     }
 }
 
@@ -98,7 +593,7 @@ fn start_reward_addition(args: Vec<String>) {
 fn add_rewards_in_folder(
     mut state: AppState,
     folder_path: &Path,
-    args: &Vec<String>,
+    args: &[String],
 ) -> io::Result<AppState> {
     for entry in fs::read_dir(folder_path)? {
         let entry = entry?;
@@ -112,7 +607,7 @@ fn add_rewards_in_folder(
     return Ok(state);
 }
 
-fn add_rewards_in_file(mut state: AppState, file_path: &Path, args: &Vec<String>) -> AppState {
+fn add_rewards_in_file(mut state: AppState, file_path: &Path, args: &[String]) -> AppState {
     let reward_name = if args.len() >= 5 {
         args[4].clone()
     } else {
@@ -137,6 +632,7 @@ fn add_rewards_in_file(mut state: AppState, file_path: &Path, args: &Vec<String>
             .expect("Missing collection name argument!")
             .to_string(),
         reward_name,
+        args.get(5).cloned(),
     );
     return state;
 }
@@ -145,6 +641,7 @@ fn reward_addition(
     file_to_encode: String,
     reward_collection: String,
     reward_name: String,
+    rarity: Option<String>,
 ) -> AppState {
     println!(
         "Adding reward to {} by name {}: path is {}",
@@ -155,14 +652,7 @@ fn reward_addition(
         .rewards
         .iter()
         .position(|collection| {
-            if collection.name == reward_collection {
-                match &collection.reward_type {
-                    RewardType::DecodeFiles(_) => true,
-                    _ => false,
-                }
-            } else {
-                false
-            }
+            collection.name == reward_collection && collection.reward_type.is_decode_files()
         })
         .expect(&f!(
             "Could not find decodeFiles collection by name {}",
@@ -173,13 +663,13 @@ fn reward_addition(
         path_before_encoding: file_to_encode,
         path_after_encoding: "".to_owned(),
         reward_name,
+        rarity,
     };
     let mut collection_to_append = state.rewards.remove(collection_to_append_index);
     match &mut collection_to_append.reward_type {
         RewardType::DecodeFiles(decode_files_reward) => 
             decode_files_reward.add_new_file(new_file, &state)
         ,
-        _ => panic!("Impossible branch! Position function above ensures that only DecodeFiles can be referenced here.")
     }
 
     state
@@ -189,64 +679,203 @@ fn reward_addition(
     return state;
 }
 
-fn edit_tasks(state: &mut AppState) {
+// Returns false once the user quits
+fn edit_tasks(state: &mut AppState, view: &mut TaskView) -> bool {
     if !state.tasks.is_empty() {
         println!("Tasks: ");
-        for i in 0..state.tasks.len() {
+        let visible_indices: Vec<usize> = view.apply(&state.tasks).iter().map(|(i, _)| *i).collect();
+        if view.is_filtering() {
+            println!(
+                "(showing {} of {} tasks)",
+                visible_indices.len(),
+                state.tasks.len()
+            );
+        }
+        for i in visible_indices {
             println!("---Task #{}---", i + 1);
             println!("{}", state.tasks[i]);
+            let remaining_prerequisites = state.tasks[i].remaining_prerequisites(&state.tasks);
+            if !remaining_prerequisites.is_empty() {
+                println!("BLOCKED by: {}", remaining_prerequisites.join(", "));
+            }
         }
         println!("------");
     }
     let prompt = if state.tasks.is_empty() {
-        "1 - add task"
+        f!("0 - quit\n1 - add task\n(enter {} at any prompt to go back)", CANCEL_INPUT)
     } else {
-        "1 - add task\n2 - remove task\n3 - tick task"
+        f!("0 - quit\n1 - add task\n2 - remove task\n3 - tick task\n4 - pause/resume per-hour task\n5 - log or correct time of per-hour task\n6 - filter and sort tasks\n7 - edit task\n(enter {} at any prompt to go back)", CANCEL_INPUT)
     };
 
-    match get_parsed_line_with_condition(Some(prompt), |int_val| {
-        return (*int_val == 2 && !state.tasks.is_empty())
-            || (*int_val == 3 && !state.tasks.is_empty())
-            || *int_val == 1;
+    let action_result = match get_parsed_line_with_condition(Some(&prompt), |int_val| {
+        return ((2..=7).contains(int_val) && !state.tasks.is_empty()) || *int_val <= 1;
     }) {
-        3 => {
-            tick_task(state);
-        }
-        2 => {
-            remove_task(state);
+        None | Some(0) => return false,
+        Some(7) => edit_task(state),
+        Some(6) => TaskView::init_view_from_cli().map(|new_view| *view = new_view),
+        Some(5) => correct_task_time(state),
+        Some(4) => pause_or_resume_task(state),
+        Some(3) => tick_task(state),
+        Some(2) => remove_task(state),
+        Some(_) => add_task(state),
+    };
+    if action_result.is_none() && !is_input_closed() && !is_interrupted() {
+        println!("Cancelled");
+    }
+    return true;
+}
+
+fn add_task(state: &mut AppState) -> Option<()> {
+    let template_index = if state.templates.is_empty() {
+        None
+    } else {
+        for template in &state.templates {
+            println!("{}", template);
         }
-        _ => {
-            add_task(state);
+        let template_name = get_line_with_condition(
+            Some("Enter the template to create the task from (empty for none): "),
+            |line| line.is_empty() || state.templates.iter().any(|template| &template.name == line),
+        )?;
+        state
+            .templates
+            .iter()
+            .position(|template| template.name == template_name)
+    };
+    let new_task = match template_index {
+        Some(index) => state.templates[index].instantiate_from_cli()?,
+        None => Task::init_task_from_cli()?,
+    };
+    push_new_task(state, new_task);
+    return Some(());
+}
+
+fn push_new_task(state: &mut AppState, new_task: Task) {
+    if new_task.has_dependency_cycle(&state.tasks) {
+        println!(
+            "{} would depend on itself through its prerequisites, so it is not added!",
+            new_task.name
+        );
+        return;
+    }
+    for prerequisite in &new_task.depends_on {
+        if !state.tasks.iter().any(|task| &task.name == prerequisite) {
+            println!(
+                "There is no task named {}, so it does not block {}",
+                prerequisite, new_task.name
+            );
         }
     }
+    state.tasks.push(new_task);
 }
 
-fn add_task(state: &mut AppState) {
-    let new_task = Task::init_task_from_cli();
-    state.tasks.push(new_task);
+fn blocked_task_names(state: &AppState) -> Vec<String> {
+    return state
+        .tasks
+        .iter()
+        .filter(|task| !task.remaining_prerequisites(&state.tasks).is_empty())
+        .map(|task| task.name.clone())
+        .collect();
 }
 
-fn remove_task(state: &mut AppState) {
-    let index_to_remove: usize = get_parsed_line_with_condition(
-        Some("Enter index of task to remove: "),
-        |int_val: &usize| *int_val > 0 && *int_val <= state.tasks.len(),
-    ) - 1;
-    state.tasks.remove(index_to_remove);
+fn remove_task(state: &mut AppState) -> Option<()> {
+    let index_to_remove: usize = select_task_from_cli(state, "remove")?;
+    state.remove_task(index_to_remove);
+    return Some(());
 }
 
-fn tick_task(state: &mut AppState) {
-    let index_to_tick: usize =
-        get_parsed_line_with_condition(Some("Enter index of task to tick: "), |int_val: &usize| {
-            *int_val > 0 && *int_val <= state.tasks.len()
-        }) - 1;
-    let tick_response = state.tasks[index_to_tick].tick_task();
+fn tick_task(state: &mut AppState) -> Option<()> {
+    let index_to_tick: usize = select_task_from_cli(state, "tick")?;
+    tick_task_at(state, index_to_tick);
+    return Some(());
+}
+
+fn tick_task_at(state: &mut AppState, index_to_tick: usize) {
+    state.sys.refresh_processes();
+    let blocked_before = blocked_task_names(state);
+    let mut task_to_tick = state.tasks.remove(index_to_tick);
+    let logged_before = task_to_tick.logged_seconds;
+    let tick_response = task_to_tick.tick_task(state);
+    let currency = task_to_tick.currency.clone();
+    task_to_tick.points_earned += tick_response.reward_acquired;
+    let logged_seconds = task_to_tick.logged_seconds - logged_before;
+    if tick_response.reward_acquired != 0.0 || logged_seconds != 0 || tick_response.task_completed {
+        state.history.push(HistoryEntry::task_payout(
+            &task_to_tick,
+            tick_response.reward_acquired,
+            logged_seconds,
+            tick_response.task_completed,
+        ));
+    }
     if tick_response.task_is_to_be_removed {
-        state.tasks.remove(index_to_tick);
+        println!("Task {} is completed and moved to the archive", task_to_tick.name);
+        state.archive.push(ArchivedTask::new(task_to_tick));
+    } else {
+        state.tasks.insert(index_to_tick, task_to_tick);
+    }
+    let blocked_after = blocked_task_names(state);
+    for unlocked in blocked_before
+        .iter()
+        .filter(|name| !blocked_after.contains(name))
+    {
+        println!("Unlocked {}!", unlocked);
+    }
+    if tick_response.reward_acquired > 0.0 {
+        state
+            .completion_streak
+            .register_completion(chrono::Local::now().date_naive());
+    }
+    state.add_points(&currency, tick_response.reward_acquired);
+}
+
+fn pause_or_resume_task(state: &mut AppState) -> Option<()> {
+    let index_to_toggle: usize = select_task_from_cli(state, "pause or resume")?;
+    pause_or_resume_task_at(state, index_to_toggle);
+    return Some(());
+}
+
+fn pause_or_resume_task_at(state: &mut AppState, index_to_toggle: usize) {
+    state.sys.refresh_processes();
+    let mut task_to_toggle = state.tasks.remove(index_to_toggle);
+    task_to_toggle.toggle_pause(state);
+    state.tasks.insert(index_to_toggle, task_to_toggle);
+}
+
+fn edit_task(state: &mut AppState) -> Option<()> {
+    let index_to_edit: usize = select_task_from_cli(state, "edit")?;
+    edit_task_at(state, index_to_edit);
+    return Some(());
+}
+
+fn edit_task_at(state: &mut AppState, index_to_edit: usize) {
+    let old_name = state.tasks[index_to_edit].name.clone();
+    let old_prerequisites = state.tasks[index_to_edit].depends_on.clone();
+    state.tasks[index_to_edit].edit_from_cli();
+    if state.tasks[index_to_edit].has_dependency_cycle(&state.tasks) {
+        println!(
+            "{} would depend on itself through its prerequisites, they are left unchanged!",
+            state.tasks[index_to_edit].name
+        );
+        state.tasks[index_to_edit].depends_on = old_prerequisites;
+    }
+    let new_name = state.tasks[index_to_edit].name.clone();
+    if new_name != old_name {
+        for task in &mut state.tasks {
+            for prerequisite in &mut task.depends_on {
+                if *prerequisite == old_name {
+                    *prerequisite = new_name.clone();
+                }
+            }
+        }
     }
-    state.cur_points += tick_response.reward_acquired;
 }
 
-fn edit_rewards(state: &mut AppState) {
+fn correct_task_time(state: &mut AppState) -> Option<()> {
+    let index_to_correct: usize = select_task_from_cli(state, "log or correct time for")?;
+    return state.tasks[index_to_correct].correct_time_from_cli();
+}
+
+// Returns false once the user quits
+fn edit_rewards(state: &mut AppState) -> bool {
     if !state.rewards.is_empty() {
         println!("Rewards: ");
         for i in 0..state.rewards.len() {
@@ -256,50 +885,58 @@ fn edit_rewards(state: &mut AppState) {
         println!("------");
     }
     let prompt = if state.rewards.is_empty() {
-        "1 - add reward"
-    } else if state.cur_points <= 0.0 {
-        "1 - add reward\n2 - remove reward"
+        f!("0 - quit\n1 - add reward\n(enter {} at any prompt to go back)", CANCEL_INPUT)
     } else {
-        "1 - add reward\n2 - remove reward\n3 - tick reward"
+        f!("0 - quit\n1 - add reward\n2 - remove reward\n3 - tick reward\n4 - edit reward\n(enter {} at any prompt to go back)", CANCEL_INPUT)
     };
 
-    match get_parsed_line_with_condition(Some(prompt), |int_val| {
-        return (*int_val == 2 && !state.rewards.is_empty())
-            || (*int_val == 3 && !state.rewards.is_empty() && state.cur_points > 0.0)
-            || *int_val == 1;
+    let action_result = match get_parsed_line_with_condition(Some(&prompt), |int_val| {
+        return ((2..=4).contains(int_val) && !state.rewards.is_empty()) || *int_val <= 1;
     }) {
-        3 => {
-            tick_reward(state);
-        }
-        2 => {
-            remove_reward(state);
-        }
-        _ => {
-            add_reward(state);
-        }
+        None | Some(0) => return false,
+        Some(4) => edit_reward(state),
+        Some(3) => tick_reward(state),
+        Some(2) => remove_reward(state),
+        Some(_) => add_reward(state),
+    };
+    if action_result.is_none() && !is_input_closed() && !is_interrupted() {
+        println!("Cancelled");
     }
+    return true;
 }
 
-fn add_reward(state: &mut AppState) {
-    let new_reward = RewardCollection::init_rewards_from_cli();
+fn add_reward(state: &mut AppState) -> Option<()> {
+    let new_reward = RewardCollection::init_rewards_from_cli()?;
     state.rewards.push(new_reward);
+    return Some(());
 }
 
-fn remove_reward(state: &mut AppState) {
-    let index_to_remove: usize = get_parsed_line_with_condition(
-        Some("Enter index of reward to remove: "),
-        |int_val: &usize| *int_val > 0 && *int_val <= state.rewards.len(),
-    ) - 1;
+fn remove_reward(state: &mut AppState) -> Option<()> {
+    let index_to_remove: usize = select_reward_from_cli(state, "remove")?;
     state.rewards.remove(index_to_remove);
+    return Some(());
+}
+
+fn edit_reward(state: &mut AppState) -> Option<()> {
+    let index_to_edit: usize = select_reward_from_cli(state, "edit")?;
+    state.rewards[index_to_edit].edit_from_cli();
+    return Some(());
 }
 
-fn tick_reward(state: &mut AppState) {
-    let index_to_tick: usize = get_parsed_line_with_condition(
-        Some("Enter index of reward to tick: "),
-        |int_val: &usize| *int_val > 0 && *int_val <= state.rewards.len(),
-    ) - 1;
+fn tick_reward(state: &mut AppState) -> Option<()> {
+    let index_to_tick: usize = select_reward_from_cli(state, "tick")?;
+    tick_reward_at(state, index_to_tick, None);
+    return Some(());
+}
+
+fn tick_reward_at(state: &mut AppState, index_to_tick: usize, chosen_file: Option<usize>) {
     let mut reward_to_tick = state.rewards.remove(index_to_tick);
-    let tick_response = reward_to_tick.tick_reward(&state);
+    let tick_response = reward_to_tick.tick_reward(state, chosen_file);
+    state.add_points(&reward_to_tick.currency, -tick_response.points_spent);
+    if tick_response.points_spent != 0.0 {
+        state
+            .history
+            .push(HistoryEntry::reward_purchase(&reward_to_tick, tick_response.points_spent));
+    }
     state.rewards.insert(index_to_tick, reward_to_tick);
-    state.cur_points -= tick_response.points_spent;
 }
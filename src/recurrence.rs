@@ -0,0 +1,309 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Weekday};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+
+use crate::cli_utils::{get_line_with_condition, get_parsed_line_with_condition};
+use crate::time_utils::{first_day_of_next_month, start_of_day};
+
+// Longest gap between two occurrences of any rule except EveryNDays, which is computed directly
+const MAX_SEARCH_DAYS: i64 = 62;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum RecurrenceRule {
+    Daily,
+    Weekdays,
+    EveryNDays(u32),
+    WeeklyOn(Vec<Weekday>),
+    Monthly(u32),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Recurrence {
+    #[serde(deserialize_with = "deserialize_repeating_rule")]
+    pub rule: RecurrenceRule,
+    pub completions_per_period: u32,
+    pub start_date: NaiveDate,
+    pub completions: Vec<DateTime<Local>>,
+}
+
+pub enum ScheduleStatus {
+    Upcoming(NaiveDate),
+    Due(u32),
+    Overdue(NaiveDate),
+    Done,
+}
+
+// Hand-edited states could hold rules that never repeat, which the schedule math can not handle
+fn deserialize_repeating_rule<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RecurrenceRule, D::Error> {
+    let rule = RecurrenceRule::deserialize(deserializer)?;
+    return match &rule {
+        RecurrenceRule::EveryNDays(0) => Err(D::Error::custom("EveryNDays needs at least one day")),
+        RecurrenceRule::WeeklyOn(days) if days.is_empty() => {
+            Err(D::Error::custom("WeeklyOn needs at least one weekday"))
+        }
+        RecurrenceRule::Monthly(day) if !(1..=31).contains(day) => {
+            Err(D::Error::custom("Monthly needs a day between 1 and 31"))
+        }
+        _ => Ok(rule),
+    };
+}
+
+impl RecurrenceRule {
+    pub fn init_rule_from_cli(choice: i64) -> Option<RecurrenceRule> {
+        return Some(match choice {
+            4 => RecurrenceRule::Weekdays,
+            5 => RecurrenceRule::EveryNDays(get_parsed_line_with_condition(
+                Some("Enter the number of days between repetitions: "),
+                |int_val: &u32| *int_val > 0,
+            )?),
+            6 => RecurrenceRule::WeeklyOn(parse_weekdays(&get_line_with_condition(
+                Some("Enter the days of the week (e.g. mon,wed,fri): "),
+                |line| !parse_weekdays(line).is_empty(),
+            )?)),
+            7 => RecurrenceRule::Monthly(get_parsed_line_with_condition(
+                Some("Enter the day of the month (days past the end of a month fall on its last day): "),
+                |int_val: &u32| *int_val > 0 && *int_val <= 31,
+            )?),
+            _ => RecurrenceRule::Daily,
+        });
+    }
+}
+
+fn parse_weekdays(line: &str) -> Vec<Weekday> {
+    let parsed: Vec<Option<Weekday>> = line
+        .split(',')
+        .map(|day| day.trim().parse::<Weekday>().ok())
+        .collect();
+    if parsed.iter().any(|day| day.is_none()) {
+        return vec![];
+    }
+    return parsed.into_iter().flatten().collect();
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    return first_day_of_next_month(date)
+        .signed_duration_since(date.with_day(1).unwrap())
+        .num_days() as u32;
+}
+
+impl Recurrence {
+    pub fn new(rule: RecurrenceRule, completions_per_period: u32) -> Recurrence {
+        return Recurrence {
+            rule,
+            completions_per_period,
+            start_date: Local::now().date_naive(),
+            completions: vec![],
+        };
+    }
+
+    fn is_occurrence(&self, date: NaiveDate) -> bool {
+        if date < self.start_date {
+            return false;
+        }
+        return match &self.rule {
+            RecurrenceRule::Daily => true,
+            RecurrenceRule::Weekdays => date.weekday().num_days_from_monday() < 5,
+            RecurrenceRule::EveryNDays(n) => {
+                date.signed_duration_since(self.start_date).num_days() % *n as i64 == 0
+            }
+            RecurrenceRule::WeeklyOn(days) => days.contains(&date.weekday()),
+            RecurrenceRule::Monthly(day) => date.day() == (*day).min(days_in_month(date)),
+        };
+    }
+
+    // Start of the period the given date belongs to, if the schedule has started by then
+    pub fn period_start(&self, date: NaiveDate) -> Option<NaiveDate> {
+        if date < self.start_date {
+            return None;
+        }
+        if let RecurrenceRule::EveryNDays(n) = self.rule {
+            let days_since_start = date.signed_duration_since(self.start_date).num_days();
+            return Some(self.start_date + Duration::days(days_since_start - days_since_start % n as i64));
+        }
+        return (0..=MAX_SEARCH_DAYS)
+            .map(|offset| date - Duration::days(offset))
+            .take_while(|candidate| *candidate >= self.start_date)
+            .find(|candidate| self.is_occurrence(*candidate));
+    }
+
+    pub fn next_occurrence_after(&self, date: NaiveDate) -> NaiveDate {
+        if let RecurrenceRule::EveryNDays(n) = self.rule {
+            return match self.period_start(date) {
+                Some(period_start) => period_start + Duration::days(n as i64),
+                None => self.start_date,
+            };
+        }
+        return (1..=MAX_SEARCH_DAYS)
+            .map(|offset| date + Duration::days(offset))
+            .find(|candidate| self.is_occurrence(*candidate))
+            .expect("Every recurrence rule repeats within two months");
+    }
+
+    fn completions_in_period(&self, now: DateTime<Local>) -> u32 {
+        return match self.period_start(now.date_naive()) {
+            Some(period_start) => self
+                .completions
+                .iter()
+                .filter(|completion| **completion >= start_of_day(period_start))
+                .count() as u32,
+            None => 0,
+        };
+    }
+
+    pub fn status(&self, now: DateTime<Local>) -> ScheduleStatus {
+        let today = now.date_naive();
+        let period_start = match self.period_start(today) {
+            Some(period_start) => period_start,
+            None => {
+                return ScheduleStatus::Upcoming(self.next_occurrence_after(self.start_date - Duration::days(1)))
+            }
+        };
+        let completions = self.completions_in_period(now);
+        return if completions >= self.completions_per_period {
+            ScheduleStatus::Done
+        } else if today > period_start {
+            ScheduleStatus::Overdue(period_start)
+        } else {
+            ScheduleStatus::Due(self.completions_per_period - completions)
+        };
+    }
+
+    pub fn can_complete(&self, now: DateTime<Local>) -> bool {
+        return match self.status(now) {
+            ScheduleStatus::Due(_) | ScheduleStatus::Overdue(_) => true,
+            ScheduleStatus::Upcoming(_) | ScheduleStatus::Done => false,
+        };
+    }
+
+    pub fn register_completion(&mut self, now: DateTime<Local>) {
+        // Completions from earlier periods no longer affect the status
+        if let Some(period_start) = self.period_start(now.date_naive()) {
+            self.completions
+                .retain(|completion| *completion >= start_of_day(period_start));
+        }
+        self.completions.push(now);
+    }
+}
+
+impl Display for RecurrenceRule {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        return match self {
+            RecurrenceRule::Daily => write!(f, "daily"),
+            RecurrenceRule::Weekdays => write!(f, "on weekdays"),
+            RecurrenceRule::EveryNDays(n) => write!(f, "every {} days", n),
+            RecurrenceRule::WeeklyOn(days) => write!(
+                f,
+                "weekly on {}",
+                days.iter()
+                    .map(|day| day.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            RecurrenceRule::Monthly(day) => write!(f, "monthly on day {}", day),
+        };
+    }
+}
+
+impl Display for ScheduleStatus {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        return match self {
+            ScheduleStatus::Upcoming(date) => write!(f, "starts on {}", date),
+            ScheduleStatus::Due(remaining) => write!(f, "due ({} left this period)", remaining),
+            ScheduleStatus::Overdue(since) => write!(f, "overdue since {}", since),
+            ScheduleStatus::Done => write!(f, "done for this period"),
+        };
+    }
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        return write!(
+            f,
+            "{}, {} per period, {}",
+            self.rule,
+            self.completions_per_period,
+            self.status(Local::now())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, NaiveDate, TimeZone, Weekday};
+    use serde_json::json;
+
+    use super::{Recurrence, RecurrenceRule, ScheduleStatus};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(2026, month, day).unwrap();
+    }
+
+    fn at(month: u32, day: u32, hour: u32) -> DateTime<Local> {
+        return Local.with_ymd_and_hms(2026, month, day, hour, 0, 0).unwrap();
+    }
+
+    // Starts on Thursday, October 1st
+    fn recurrence(rule: RecurrenceRule, completions_per_period: u32) -> Recurrence {
+        return Recurrence {
+            rule,
+            completions_per_period,
+            start_date: date(10, 1),
+            completions: vec![],
+        };
+    }
+
+    #[test]
+    fn periods_start_at_the_last_occurrence() {
+        let weekdays = recurrence(RecurrenceRule::Weekdays, 1);
+        assert_eq!(weekdays.period_start(date(10, 18)), Some(date(10, 16)));
+        assert_eq!(weekdays.next_occurrence_after(date(10, 16)), date(10, 19));
+        let weekly = recurrence(RecurrenceRule::WeeklyOn(vec![Weekday::Mon, Weekday::Thu]), 1);
+        assert_eq!(weekly.period_start(date(10, 7)), Some(date(10, 5)));
+        assert_eq!(weekly.next_occurrence_after(date(10, 5)), date(10, 8));
+        let every_third_day = recurrence(RecurrenceRule::EveryNDays(3), 1);
+        assert_eq!(every_third_day.period_start(date(10, 6)), Some(date(10, 4)));
+        assert_eq!(every_third_day.next_occurrence_after(date(10, 6)), date(10, 7));
+        assert_eq!(weekly.period_start(date(9, 30)), None);
+    }
+
+    #[test]
+    fn monthly_days_past_the_end_fall_on_the_last_day() {
+        let monthly = recurrence(RecurrenceRule::Monthly(31), 1);
+        assert_eq!(monthly.period_start(date(11, 15)), Some(date(10, 31)));
+        assert_eq!(monthly.next_occurrence_after(date(10, 31)), date(11, 30));
+        assert_eq!(monthly.period_start(date(10, 15)), None);
+    }
+
+    #[test]
+    fn status_follows_the_completions_of_the_period() {
+        let mut daily = recurrence(RecurrenceRule::Daily, 2);
+        assert!(matches!(daily.status(at(10, 8, 9)), ScheduleStatus::Due(2)));
+        daily.register_completion(at(10, 8, 10));
+        assert!(matches!(daily.status(at(10, 8, 11)), ScheduleStatus::Due(1)));
+        daily.register_completion(at(10, 8, 12));
+        assert!(matches!(daily.status(at(10, 8, 13)), ScheduleStatus::Done));
+        assert!(!daily.can_complete(at(10, 8, 13)));
+        assert!(matches!(daily.status(at(10, 9, 9)), ScheduleStatus::Due(2)));
+        let weekly = recurrence(RecurrenceRule::WeeklyOn(vec![Weekday::Mon]), 1);
+        assert!(matches!(weekly.status(at(10, 7, 9)), ScheduleStatus::Overdue(start) if start == date(10, 5)));
+        assert!(matches!(weekly.status(at(10, 2, 9)), ScheduleStatus::Upcoming(start) if start == date(10, 5)));
+    }
+
+    #[test]
+    fn rules_that_never_repeat_are_rejected() {
+        let parse = |rule: RecurrenceRule| {
+            let value = json!({
+                "rule": rule,
+                "completions_per_period": 1,
+                "start_date": "2026-10-01",
+                "completions": [],
+            });
+            return serde_json::from_value::<Recurrence>(value).is_ok();
+        };
+        assert!(parse(RecurrenceRule::WeeklyOn(vec![Weekday::Fri])));
+        assert!(!parse(RecurrenceRule::WeeklyOn(vec![])));
+        assert!(!parse(RecurrenceRule::EveryNDays(0)));
+        assert!(!parse(RecurrenceRule::Monthly(0)));
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+
+use crate::currency::format_amount;
+use crate::history::{HistoryEntry, HistoryKind};
+use crate::time_utils::CalendarPeriod;
+
+#[derive(PartialEq, Eq)]
+pub enum ReportFormat {
+    Table,
+    Json,
+}
+
+pub struct ReportOptions {
+    // Both ends are included
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub format: ReportFormat,
+}
+
+impl ReportOptions {
+    // Defaults to today; "day", "week" and "month" mean the current calendar period
+    pub fn from_args(args: &[String]) -> Result<ReportOptions, String> {
+        return ReportOptions::from_args_on(args, Local::now().date_naive());
+    }
+
+    fn from_args_on(args: &[String], today: NaiveDate) -> Result<ReportOptions, String> {
+        let mut options = ReportOptions {
+            from: today,
+            to: today,
+            format: ReportFormat::Table,
+        };
+        let mut remaining_args = args.iter();
+        while let Some(arg) = remaining_args.next() {
+            let period = match arg.as_str() {
+                "day" => Some(CalendarPeriod::Day),
+                "week" => Some(CalendarPeriod::Week),
+                "month" => Some(CalendarPeriod::Month),
+                _ => None,
+            };
+            if let Some(period) = period {
+                options.from = period.period_start(today);
+                options.to = period.next_period_start(today).pred_opt().unwrap();
+                continue;
+            }
+            let value = remaining_args.next().ok_or(f!("Missing value for {}", arg))?;
+            match arg.as_str() {
+                "--from" => options.from = parse_date(value)?,
+                "--to" => options.to = parse_date(value)?,
+                "--format" => {
+                    options.format = match value.as_str() {
+                        "table" => ReportFormat::Table,
+                        "json" => ReportFormat::Json,
+                        _ => return Err(f!("{} is not table or json", value)),
+                    }
+                }
+                _ => return Err(f!("Unknown option {}", arg)),
+            }
+        }
+        if options.from > options.to {
+            return Err(f!("The range from {} to {} is empty", options.from, options.to));
+        }
+        return Ok(options);
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    return NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| f!("{} is not a YYYY-MM-DD date", value));
+}
+
+#[derive(Serialize)]
+pub struct ReportRow {
+    pub name: String,
+    pub currency: String,
+    pub points: f64,
+    pub hours: f64,
+}
+
+#[derive(Serialize)]
+pub struct ReportTotal {
+    pub currency: String,
+    pub earned: f64,
+    pub spent: f64,
+    pub penalties: f64,
+    pub interest: f64,
+    // Received minus given away in exchanges
+    pub exchanged: f64,
+    pub net: f64,
+}
+
+#[derive(Serialize)]
+pub struct Report {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub by_task: Vec<ReportRow>,
+    pub by_tag: Vec<ReportRow>,
+    pub by_project: Vec<ReportRow>,
+    pub by_reward: Vec<ReportRow>,
+    pub penalties: Vec<ReportRow>,
+    pub interest: Vec<ReportRow>,
+    pub exchanges: Vec<ReportRow>,
+    pub totals: Vec<ReportTotal>,
+}
+
+// Sums points and logged time per (name, currency)
+#[derive(Default)]
+struct Grouping {
+    sums: BTreeMap<(String, String), (f64, i64)>,
+}
+
+impl Grouping {
+    fn add(&mut self, name: &str, entry: &HistoryEntry, points: f64) {
+        let sum = self
+            .sums
+            .entry((name.to_string(), entry.currency.clone()))
+            .or_insert((0.0, 0));
+        sum.0 += points;
+        sum.1 += entry.logged_seconds;
+    }
+
+    fn into_rows(self) -> Vec<ReportRow> {
+        return self
+            .sums
+            .into_iter()
+            .map(|((name, currency), (points, seconds))| ReportRow {
+                name,
+                currency,
+                points,
+                hours: seconds as f64 / 3600.0,
+            })
+            .collect();
+    }
+}
+
+impl Report {
+    // Earnings keep their sign, spending, penalties and interest are shown as positive amounts
+    pub fn build(history: &[HistoryEntry], from: NaiveDate, to: NaiveDate) -> Report {
+        let (mut by_task, mut by_tag, mut by_project) = (Grouping::default(), Grouping::default(), Grouping::default());
+        let (mut by_reward, mut penalties) = (Grouping::default(), Grouping::default());
+        let (mut interest, mut exchanges) = (Grouping::default(), Grouping::default());
+        let mut totals: BTreeMap<String, ReportTotal> = BTreeMap::new();
+        for entry in history.iter().filter(|entry| {
+            let date = entry.at.date_naive();
+            return from <= date && date <= to;
+        }) {
+            let total = totals.entry(entry.currency.clone()).or_insert(ReportTotal {
+                currency: entry.currency.clone(),
+                earned: 0.0,
+                spent: 0.0,
+                penalties: 0.0,
+                interest: 0.0,
+                exchanged: 0.0,
+                net: 0.0,
+            });
+            total.net += entry.amount;
+            match entry.kind {
+                HistoryKind::TaskPayout => {
+                    total.earned += entry.amount;
+                    by_task.add(&entry.name, entry, entry.amount);
+                    for tag in &entry.tags {
+                        by_tag.add(tag, entry, entry.amount);
+                    }
+                    by_project.add(entry.project.as_deref().unwrap_or("(no project)"), entry, entry.amount);
+                }
+                HistoryKind::RewardPurchase => {
+                    total.spent -= entry.amount;
+                    by_reward.add(&entry.name, entry, -entry.amount);
+                }
+                HistoryKind::DeadlinePenalty => {
+                    total.penalties -= entry.amount;
+                    penalties.add(&entry.name, entry, -entry.amount);
+                }
+                HistoryKind::DebtInterest => {
+                    total.interest -= entry.amount;
+                    interest.add(&entry.name, entry, -entry.amount);
+                }
+                HistoryKind::Exchange => {
+                    total.exchanged += entry.amount;
+                    exchanges.add(&f!("exchange with {}", entry.name), entry, entry.amount);
+                }
+            }
+        }
+        return Report {
+            from,
+            to,
+            by_task: by_task.into_rows(),
+            by_tag: by_tag.into_rows(),
+            by_project: by_project.into_rows(),
+            by_reward: by_reward.into_rows(),
+            penalties: penalties.into_rows(),
+            interest: interest.into_rows(),
+            exchanges: exchanges.into_rows(),
+            totals: totals.into_values().collect(),
+        };
+    }
+}
+
+fn rounded(amount: f64) -> f64 {
+    return (amount * 100.0).round() / 100.0;
+}
+
+fn write_section(f: &mut Formatter, title: &str, rows: &[ReportRow], with_hours: bool) -> std::fmt::Result {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "\n{}", title)?;
+    let name_width = rows.iter().map(|row| row.name.chars().count()).max().unwrap_or(0);
+    for row in rows {
+        write!(
+            f,
+            "  {:<width$}  {:>14}",
+            row.name,
+            format_amount(rounded(row.points), &row.currency),
+            width = name_width
+        )?;
+        if with_hours && row.hours > 0.0 {
+            write!(f, "  {:>7}h", rounded(row.hours))?;
+        }
+        writeln!(f)?;
+    }
+    return Ok(());
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        writeln!(f, "Report from {} to {}", self.from, self.to)?;
+        if self.totals.is_empty() {
+            return write!(f, "Nothing was earned or spent in this range");
+        }
+        write_section(f, "Earned per task", &self.by_task, true)?;
+        write_section(f, "Earned per tag", &self.by_tag, true)?;
+        write_section(f, "Earned per project", &self.by_project, true)?;
+        write_section(f, "Spent per reward collection", &self.by_reward, false)?;
+        write_section(f, "Deadline penalties", &self.penalties, false)?;
+        write_section(f, "Debt interest", &self.interest, false)?;
+        write_section(f, "Exchanges", &self.exchanges, false)?;
+        writeln!(f, "\nTotals")?;
+        for total in &self.totals {
+            writeln!(
+                f,
+                "  {}: earned {}, spent {}, penalties {}, interest {}, exchanged {}, net {}",
+                total.currency,
+                rounded(total.earned),
+                rounded(total.spent),
+                rounded(total.penalties),
+                rounded(total.interest),
+                rounded(total.exchanged),
+                rounded(total.net)
+            )?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, NaiveDate, TimeZone};
+
+    use super::{Report, ReportFormat, ReportOptions};
+    use crate::history::{HistoryEntry, HistoryKind};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    }
+
+    fn args(values: &[&str]) -> Vec<String> {
+        return values.iter().map(|value| value.to_string()).collect();
+    }
+
+    fn entry(at: (u32, u32, u32), kind: HistoryKind, name: &str, amount: f64) -> HistoryEntry {
+        let (day, hour, minute) = at;
+        return HistoryEntry {
+            at: Local.with_ymd_and_hms(2026, 10, day, hour, minute, 0).unwrap(),
+            kind,
+            name: name.to_string(),
+            currency: "points".to_string(),
+            amount,
+            tags: vec!["work".to_string()],
+            project: None,
+            logged_seconds: 0,
+            completed: false,
+        };
+    }
+
+    #[test]
+    fn periods_cover_the_calendar_period_of_today() {
+        let today = date(2026, 10, 21);
+        let day = ReportOptions::from_args_on(&args(&[]), today).unwrap();
+        assert_eq!((day.from, day.to), (today, today));
+        let week = ReportOptions::from_args_on(&args(&["week"]), today).unwrap();
+        assert_eq!((week.from, week.to), (date(2026, 10, 19), date(2026, 10, 25)));
+        let month = ReportOptions::from_args_on(&args(&["month", "--format", "json"]), today).unwrap();
+        assert_eq!((month.from, month.to), (date(2026, 10, 1), date(2026, 10, 31)));
+        assert!(month.format == ReportFormat::Json);
+    }
+
+    #[test]
+    fn custom_ranges_are_validated() {
+        let today = date(2026, 10, 21);
+        let custom = ReportOptions::from_args_on(&args(&["--from", "2026-10-02", "--to", "2026-10-05"]), today).unwrap();
+        assert_eq!((custom.from, custom.to), (date(2026, 10, 2), date(2026, 10, 5)));
+        assert!(ReportOptions::from_args_on(&args(&["--from", "2026-10-22"]), today).is_err());
+        assert!(ReportOptions::from_args_on(&args(&["--from", "yesterday"]), today).is_err());
+        assert!(ReportOptions::from_args_on(&args(&["--to"]), today).is_err());
+        assert!(ReportOptions::from_args_on(&args(&["--format", "xml"]), today).is_err());
+        assert!(ReportOptions::from_args_on(&args(&["--sort", "name"]), today).is_err());
+    }
+
+    #[test]
+    fn both_ends_of_the_range_are_included() {
+        let history = vec![
+            entry((9, 23, 59), HistoryKind::TaskPayout, "before", 1.0),
+            entry((10, 0, 0), HistoryKind::TaskPayout, "first", 2.0),
+            entry((12, 23, 59), HistoryKind::TaskPayout, "last", 4.0),
+            entry((13, 0, 0), HistoryKind::TaskPayout, "after", 8.0),
+        ];
+        let report = Report::build(&history, date(2026, 10, 10), date(2026, 10, 12));
+        let names: Vec<&str> = report.by_task.iter().map(|row| row.name.as_str()).collect();
+        assert_eq!(names, vec!["first", "last"]);
+        assert_eq!(report.totals[0].net, 6.0);
+    }
+
+    #[test]
+    fn groupings_agree_with_the_totals() {
+        let mut history = vec![
+            entry((10, 9, 0), HistoryKind::TaskPayout, "write", 5.0),
+            entry((10, 10, 0), HistoryKind::TaskPayout, "write", -2.0),
+            entry((10, 11, 0), HistoryKind::RewardPurchase, "movies", -3.0),
+            entry((10, 12, 0), HistoryKind::DeadlinePenalty, "write", -1.0),
+            entry((10, 13, 0), HistoryKind::DebtInterest, "debt interest", -0.5),
+        ];
+        history[0].logged_seconds = 5400;
+        history[1].project = Some("book".to_string());
+        let report = Report::build(&history, date(2026, 10, 10), date(2026, 10, 10));
+        assert_eq!(report.by_task.len(), 1);
+        assert_eq!(report.by_task[0].points, 3.0);
+        assert_eq!(report.by_task[0].hours, 1.5);
+        assert_eq!(report.by_tag[0].points, 3.0);
+        let projects: Vec<(&str, f64)> = report
+            .by_project
+            .iter()
+            .map(|row| (row.name.as_str(), row.points))
+            .collect();
+        assert_eq!(projects, vec![("(no project)", 5.0), ("book", -2.0)]);
+        assert_eq!(report.by_reward[0].points, 3.0);
+        assert_eq!(report.penalties[0].points, 1.0);
+        assert_eq!(report.interest[0].points, 0.5);
+        let total = &report.totals[0];
+        assert_eq!(
+            (total.earned, total.spent, total.penalties, total.interest, total.net),
+            (3.0, 3.0, 1.0, 0.5, -1.5)
+        );
+    }
+
+    #[test]
+    fn exchanges_are_counted_on_both_sides() {
+        let mut history: Vec<HistoryEntry> = HistoryEntry::exchange("points", "gems", 4.0, 2.0).to_vec();
+        history.iter_mut().for_each(|entry| entry.at = Local.with_ymd_and_hms(2026, 10, 10, 12, 0, 0).unwrap());
+        let report = Report::build(&history, date(2026, 10, 10), date(2026, 10, 10));
+        let nets: Vec<(&str, f64)> = report
+            .totals
+            .iter()
+            .map(|total| (total.currency.as_str(), total.net))
+            .collect();
+        assert_eq!(nets, vec![("gems", 2.0), ("points", -4.0)]);
+        assert_eq!(report.exchanges.len(), 2);
+    }
+}
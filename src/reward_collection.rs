@@ -3,18 +3,22 @@ use std::fmt::{Display, Formatter};
 use serde::{Deserialize, Serialize};
 
 use crate::app_state::AppState;
-use crate::crypto_utils;
+use crate::currency::{default_currency, format_amount, get_currency_from_cli};
 use crate::io_utils::decode_file_from_moving;
 use crate::io_utils::encode_file_by_moving;
 use crate::io_utils::encode_file_by_moving_initial;
+use crate::selection::select_from_cli;
 use crate::task::RewardPointTransferProtocol;
+use crate::time_utils::{format_duration, start_of_day, CalendarPeriod};
 use chrono;
 use chrono::DateTime;
+use chrono::Duration;
 use chrono::Local;
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::Rng;
 
-use crate::cli_utils::{
-    self, get_line, get_line_with_condition, get_parsed_line, get_parsed_line_with_condition,
-};
+use crate::cli_utils::{get_line, get_line_with_condition, get_parsed_line, get_parsed_line_with_condition};
 
 #[derive(Serialize, Deserialize)]
 pub enum RewardType {
@@ -22,16 +26,17 @@ pub enum RewardType {
 }
 impl RewardType {
     pub fn is_decode_files(&self) -> bool {
-        match self {
-            RewardType::DecodeFiles(_) => true,
-            _ => false,
-        }
+        return matches!(self, RewardType::DecodeFiles(_));
     }
 
-    pub fn execute_reward(&mut self, state:&AppState) -> Option<()> {
+    // A chosen file skips the prompt, mystery collections roll their own anyway
+    pub fn execute_reward(&mut self, state: &AppState, chosen_file: Option<usize>) -> Option<()> {
         match self {
             RewardType::DecodeFiles(reward) => {
-                let file_index = reward.choose_file("Enter index of file to decode: ")?;
+                let file_index = match chosen_file {
+                    Some(file_index) if reward.is_chosen_by_user() => file_index,
+                    _ => reward.pick_file("Enter index or name of file to decode: ", state)?,
+                };
                 let chosen_file = &reward.files_to_decode[file_index];
                 decode_file_from_moving(&chosen_file.path_after_encoding, &chosen_file.path_before_encoding);
                 reward.files_to_decode.remove(file_index);
@@ -39,10 +44,13 @@ impl RewardType {
         };
         return Some(());
     }
-    pub fn activate_reward(&mut self, state:&AppState) -> Option<()> {
+    pub fn activate_reward(&mut self, state: &AppState, chosen_file: Option<usize>) -> Option<()> {
         match self {
             RewardType::DecodeFiles(reward) => {
-                let file_index = reward.choose_file("Enter index of file to rent: ")?;
+                let file_index = match chosen_file {
+                    Some(file_index) if reward.is_chosen_by_user() => file_index,
+                    _ => reward.pick_file("Enter index or name of file to rent: ", state)?,
+                };
                 reward.currently_decoded_file_index = Some(file_index);
                 let chosen_file = &reward.files_to_decode[file_index];
                 decode_file_from_moving(&chosen_file.path_after_encoding, &chosen_file.path_before_encoding);
@@ -50,14 +58,14 @@ impl RewardType {
         };
         return Some(());
     }
-    pub fn deactivate_reward(&mut self, state:&AppState) -> Option<()> {
+    pub fn deactivate_reward(&mut self, _state: &AppState) -> Option<()> {
         match self {
             RewardType::DecodeFiles(reward) => {
                 let file_index = reward
                     .currently_decoded_file_index
                     .expect("No file is decoded even though reward is being deactivated!");
                 let chosen_file = &reward.files_to_decode[file_index];
-                let path_after_encoding = encode_file_by_moving(&chosen_file.path_after_encoding, &chosen_file.path_before_encoding);
+                encode_file_by_moving(&chosen_file.path_after_encoding, &chosen_file.path_before_encoding);
                 reward.currently_decoded_file_index = None;
             }
         };
@@ -69,18 +77,48 @@ impl RewardType {
 pub struct DecodeFilesReward {
     pub files_to_decode: Vec<SingularFileToDecode>,
     pub currently_decoded_file_index: Option<usize>,
+    #[serde(default)]
+    pub selection_mode: FileSelectionMode,
 }
 impl DecodeFilesReward {
     pub fn get_default() -> DecodeFilesReward {
         return DecodeFilesReward {
             files_to_decode: vec![],
             currently_decoded_file_index: None,
+            selection_mode: FileSelectionMode::Chosen,
         };
     }
     pub fn add_new_file(&mut self, mut new_file: SingularFileToDecode, state:&AppState) {
+        if let (FileSelectionMode::Mystery(config), Some(rarity)) = (&self.selection_mode, &new_file.rarity) {
+            if config.tier_rank(rarity).is_none() {
+                panic!("Rarity tier {} does not exist in this collection!", rarity);
+            }
+        }
         new_file.path_after_encoding =  encode_file_by_moving_initial(&state.folder_pool, &new_file.path_before_encoding);
         self.files_to_decode.push(new_file);
     }
+    pub fn edit_selection_mode_from_cli(&mut self) -> Option<()> {
+        self.selection_mode = match get_parsed_line_with_condition(
+            Some("Choose file selection: \n1 - decode chosen files\n2 - decode random files (mystery)"),
+            |int_val: &i64| *int_val == 1 || *int_val == 2,
+        )? {
+            2 => FileSelectionMode::Mystery(MysteryConfig::init_config_from_cli()?),
+            _ => FileSelectionMode::Chosen,
+        };
+        if let FileSelectionMode::Mystery(config) = &self.selection_mode {
+            for file in &self.files_to_decode {
+                if let Some(rarity) = &file.rarity {
+                    if config.tier_rank(rarity).is_none() {
+                        println!("Tier {} of {} no longer exists, it is rolled as the most common tier", rarity, file.reward_name);
+                    }
+                }
+            }
+        }
+        return Some(());
+    }
+    pub fn is_chosen_by_user(&self) -> bool {
+        return matches!(self.selection_mode, FileSelectionMode::Chosen);
+    }
     pub fn choose_file(&self, prompt: &str) -> Option<usize> {
         if self.files_to_decode.is_empty() {
             return None;
@@ -88,23 +126,127 @@ impl DecodeFilesReward {
         for i in 0..self.files_to_decode.len() {
             println!("{}.\t{}", i + 1, self.files_to_decode[i]);
         }
-        let file_index: usize = get_parsed_line_with_condition(Some(prompt), |int_val: &usize| {
-            *int_val > 0 && *int_val <= self.files_to_decode.len()
-        }) - 1;
-        return Some(file_index);
+        let names: Vec<&str> = self.files_to_decode.iter().map(|file| file.reward_name.as_str()).collect();
+        return select_from_cli(prompt, &names);
+    }
+    pub fn pick_file(&self, prompt: &str, state: &AppState) -> Option<usize> {
+        return match &self.selection_mode {
+            FileSelectionMode::Chosen => self.choose_file(prompt),
+            FileSelectionMode::Mystery(config) => {
+                let streak = state.completion_streak.active_streak(Local::now().date_naive());
+                let file_index = self.roll_file(config, streak)?;
+                println!("Mystery reward: {}", self.files_to_decode[file_index]);
+                Some(file_index)
+            }
+        };
+    }
+    pub fn roll_file(&self, config: &MysteryConfig, streak: u32) -> Option<usize> {
+        if self.files_to_decode.is_empty() {
+            return None;
+        }
+        let weights = self.file_weights(config, streak);
+        let mut rng = rand::thread_rng();
+        return match WeightedIndex::new(&weights) {
+            Ok(distribution) => Some(distribution.sample(&mut rng)),
+            Err(_) => Some(rng.gen_range(0..self.files_to_decode.len())),
+        };
+    }
+    fn file_weights(&self, config: &MysteryConfig, streak: u32) -> Vec<f64> {
+        let tier_ranks: Vec<usize> = self
+            .files_to_decode
+            .iter()
+            .map(|file| match &file.rarity {
+                Some(rarity) => config.tier_rank(rarity).unwrap_or(0),
+                None => 0,
+            })
+            .collect();
+        // Tier weight is split between the files in it, so adding files does not shift the odds between tiers
+        return tier_ranks
+            .iter()
+            .map(|rank| {
+                let files_in_tier = tier_ranks.iter().filter(|other| *other == rank).count();
+                config.tier_weight(*rank, streak) / files_in_tier as f64
+            })
+            .collect();
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum FileSelectionMode {
+    Chosen,
+    Mystery(MysteryConfig),
+}
+impl Default for FileSelectionMode {
+    fn default() -> FileSelectionMode {
+        return FileSelectionMode::Chosen;
     }
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct MysteryConfig {
+    // Ordered from the most common tier to the rarest one
+    pub rarity_tiers: Vec<RarityTier>,
+    pub streak_bonus: f64,
+}
+impl MysteryConfig {
+    pub fn init_config_from_cli() -> Option<MysteryConfig> {
+        let tier_count: usize = get_parsed_line_with_condition(
+            Some("Enter the number of rarity tiers (from most common to rarest): "),
+            |int_val: &usize| *int_val > 0,
+        )?;
+        let rarity_tiers = (0..tier_count)
+            .map(|i| {
+                Some(RarityTier {
+                    name: get_line(Some(&f!("Enter the name of tier #{}: ", i + 1)))?,
+                    weight: get_parsed_line_with_condition(
+                        Some(&f!("Enter the weight of tier #{}: ", i + 1)),
+                        |float_val: &f64| *float_val >= 0.0,
+                    )?,
+                })
+            })
+            .collect::<Option<Vec<RarityTier>>>()?;
+        let streak_bonus: f64 = get_parsed_line_with_condition(
+            Some("Enter how much rarer tiers gain in weight per day of task completion streak (0 to disable): "),
+            |float_val: &f64| *float_val >= 0.0,
+        )?;
+        return Some(MysteryConfig {
+            rarity_tiers,
+            streak_bonus,
+        });
+    }
+    pub fn tier_rank(&self, tier_name: &str) -> Option<usize> {
+        return self.rarity_tiers.iter().position(|tier| tier.name == tier_name);
+    }
+    pub fn tier_weight(&self, rank: usize, streak: u32) -> f64 {
+        let base_weight = match self.rarity_tiers.get(rank) {
+            Some(tier) => tier.weight,
+            None => 1.0,
+        };
+        return base_weight * (1.0 + self.streak_bonus * streak as f64 * rank as f64);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RarityTier {
+    pub name: String,
+    pub weight: f64,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SingularFileToDecode {
     pub path_before_encoding: String,
     pub reward_name: String,
-    pub path_after_encoding: String
+    pub path_after_encoding: String,
+    #[serde(default)]
+    pub rarity: Option<String>,
 }
 
 impl Display for SingularFileToDecode {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        return write!(f, "{} --- {}", self.reward_name, self.path_before_encoding);
+        return match &self.rarity {
+            Some(rarity) => write!(f, "{} --- {} [{}]", self.reward_name, self.path_before_encoding, rarity),
+            None => write!(f, "{} --- {}", self.reward_name, self.path_before_encoding),
+        };
     }
 }
 
@@ -114,66 +256,277 @@ pub struct RewardCollection {
     pub description: String,
     pub reward_type: RewardType,
     pub cost: f64,
+    #[serde(default = "default_currency")]
+    pub currency: String,
     pub spending_protocol: RewardPointTransferProtocol,
+    #[serde(default)]
+    pub limits: PurchaseLimits,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct PurchaseLimits {
+    pub cooldown_minutes: Option<i64>,
+    pub period_cap: Option<PeriodCap>,
+    pub stock: Option<u32>,
+    pub purchase_times: Vec<DateTime<Local>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PeriodCap {
+    pub period: CalendarPeriod,
+    pub max_purchases: u32,
+}
+
+pub enum Availability {
+    Available,
+    OutOfStock,
+    AvailableAt(DateTime<Local>),
+}
+
+impl PurchaseLimits {
+    pub fn init_limits_from_cli() -> Option<PurchaseLimits> {
+        let has_limits = get_line_with_condition(Some("Limit how often the reward can be bought?(y/n)"), |line| {
+            line.to_lowercase() == "y" || line.to_lowercase() == "n"
+        })?
+        .to_lowercase()
+            == "y";
+        if !has_limits {
+            return Some(PurchaseLimits::default());
+        }
+        let cooldown_minutes: i64 = get_parsed_line_with_condition(
+            Some("Enter the cooldown between purchases in minutes (0 for none): "),
+            |int_val: &i64| *int_val >= 0,
+        )?;
+        let period = match get_parsed_line_with_condition(
+            Some("Choose purchase cap period: \n0 - no cap\n1 - per day\n2 - per week\n3 - per month"),
+            |int_val: &i64| (0..=3).contains(int_val),
+        )? {
+            1 => Some(CalendarPeriod::Day),
+            2 => Some(CalendarPeriod::Week),
+            3 => Some(CalendarPeriod::Month),
+            _ => None,
+        };
+        let period_cap = match period {
+            Some(period) => Some(PeriodCap {
+                period,
+                max_purchases: get_parsed_line_with_condition(
+                    Some(&f!("Enter the maximum number of purchases per {}: ", period.name())),
+                    |int_val: &u32| *int_val > 0,
+                )?,
+            }),
+            None => None,
+        };
+        let stock: i64 = get_parsed_line_with_condition(
+            Some("Enter the stock of the reward (-1 for unlimited): "),
+            |int_val: &i64| *int_val >= -1,
+        )?;
+        return Some(PurchaseLimits {
+            cooldown_minutes: if cooldown_minutes > 0 { Some(cooldown_minutes) } else { None },
+            period_cap,
+            stock: if stock >= 0 { Some(stock as u32) } else { None },
+            purchase_times: vec![],
+        });
+    }
+
+    pub fn availability(&self, now: DateTime<Local>) -> Availability {
+        if self.stock == Some(0) {
+            return Availability::OutOfStock;
+        }
+        let mut available_at = now;
+        if let (Some(cooldown_minutes), Some(last_purchase)) = (self.cooldown_minutes, self.purchase_times.last()) {
+            available_at = available_at.max(*last_purchase + Duration::minutes(cooldown_minutes));
+        }
+        if let Some(cap) = &self.period_cap {
+            let today = now.date_naive();
+            let period_start = start_of_day(cap.period.period_start(today));
+            let purchases_in_period = self.purchase_times.iter().filter(|time| **time >= period_start).count();
+            if purchases_in_period >= cap.max_purchases as usize {
+                available_at = available_at.max(start_of_day(cap.period.next_period_start(today)));
+            }
+        }
+        return if available_at > now {
+            Availability::AvailableAt(available_at)
+        } else {
+            Availability::Available
+        };
+    }
+
+    pub fn register_purchase(&mut self, now: DateTime<Local>) {
+        if let Some(stock) = self.stock {
+            self.stock = Some(stock.saturating_sub(1));
+        }
+        // Only the latest purchase and the ones in the current cap period still matter
+        let keep_after = match &self.period_cap {
+            Some(cap) => start_of_day(cap.period.period_start(now.date_naive())),
+            None => now,
+        };
+        self.purchase_times.retain(|time| *time >= keep_after);
+        self.purchase_times.push(now);
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        return self.cooldown_minutes.is_none() && self.period_cap.is_none() && self.stock.is_none();
+    }
+}
+
+impl Display for PurchaseLimits {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let mut parts = vec![];
+        if let Some(cooldown_minutes) = self.cooldown_minutes {
+            parts.push(format!("cooldown {}", format_duration(Duration::minutes(cooldown_minutes))));
+        }
+        if let Some(cap) = &self.period_cap {
+            parts.push(format!("{} per {}", cap.max_purchases, cap.period.name()));
+        }
+        if let Some(stock) = self.stock {
+            parts.push(format!("{} in stock", stock));
+        }
+        let now = Local::now();
+        let availability = match self.availability(now) {
+            Availability::Available => "available now".to_string(),
+            Availability::OutOfStock => "out of stock".to_string(),
+            Availability::AvailableAt(time) => {
+                format!("next purchase in {}", format_duration(time.signed_duration_since(now)))
+            }
+        };
+        return write!(f, "{}; {}", parts.join(", "), availability);
+    }
 }
 
 impl RewardCollection {
-    pub fn init_rewards_from_cli() -> RewardCollection {
-        let name = get_line(Some("Enter the name of the reward: "));
-        let description = get_line(Some("Enter the description of the reward: "));
+    pub fn init_rewards_from_cli() -> Option<RewardCollection> {
+        let name = get_line(Some("Enter the name of the reward: "))?;
+        let description = get_line(Some("Enter the description of the reward: "))?;
         let spending_protocol = match get_parsed_line_with_condition(
             Some("Choose reward cost type: \n1 - per hour cost\n2 - one-time cost"),
             |int_val| *int_val == 1 || *int_val == 2,
-        ) {
+        )? {
             1 => RewardPointTransferProtocol::HourlyTransfer(None),
             _ => RewardPointTransferProtocol::SingularTransfer,
         };
-        let cost: f64 = get_parsed_line(Some("Enter the cost amount: "));
+        let cost: f64 = get_parsed_line(Some("Enter the cost amount: "))?;
+        let currency = get_currency_from_cli()?;
 
         let reward_type = match get_parsed_line_with_condition(
-            Some("Choose reward type: \n1 - decode files"),
-            |int_val: &i64| *int_val == 1,
-        ) {
+            Some("Choose reward type: \n1 - decode chosen files\n2 - decode random files (mystery)"),
+            |int_val: &i64| *int_val == 1 || *int_val == 2,
+        )? {
+            2 => RewardType::DecodeFiles(DecodeFilesReward {
+                selection_mode: FileSelectionMode::Mystery(MysteryConfig::init_config_from_cli()?),
+                ..DecodeFilesReward::get_default()
+            }),
             _ => RewardType::DecodeFiles(DecodeFilesReward::get_default()),
         };
 
-        return RewardCollection {
+        let limits = PurchaseLimits::init_limits_from_cli()?;
+
+        return Some(RewardCollection {
             spending_protocol,
             cost,
+            currency,
             description,
             name,
             reward_type,
-        };
+            limits,
+        });
+    }
+    // Changes one field at a time, a running rental and the files to decode are kept
+    pub fn edit_from_cli(&mut self) {
+        loop {
+            match get_parsed_line_with_condition(
+                Some("Choose field to edit: \n0 - done\n1 - name\n2 - description\n3 - cost\n4 - currency\n5 - purchase limits\n6 - file selection"),
+                |int_val: &i64| (0..=6).contains(int_val),
+            ) {
+                None | Some(0) => return,
+                Some(field) => {
+                    if self.edit_field_from_cli(field).is_none() {
+                        println!("Edit cancelled");
+                    }
+                }
+            }
+            println!("{}", self);
+        }
     }
-    pub fn tick_reward(&mut self, state:&AppState) -> TickResponse {
+    fn edit_field_from_cli(&mut self, field: i64) -> Option<()> {
+        match field {
+            1 => {
+                self.name = get_line_with_condition(Some("Enter the name of the reward: "), |line| {
+                    !line.trim().is_empty()
+                })?
+            }
+            2 => self.description = get_line(Some("Enter the description of the reward: "))?,
+            3 => self.cost = get_parsed_line(Some("Enter the cost amount: "))?,
+            4 => self.currency = get_currency_from_cli()?,
+            5 => {
+                // Past purchases still count towards the new cooldown and cap
+                let mut limits = PurchaseLimits::init_limits_from_cli()?;
+                limits.purchase_times = std::mem::take(&mut self.limits.purchase_times);
+                self.limits = limits;
+            }
+            _ => match &mut self.reward_type {
+                RewardType::DecodeFiles(reward) => reward.edit_selection_mode_from_cli()?,
+            },
+        }
+        return Some(());
+    }
+    // A chosen file is decoded instead of asking for one, when the collection lets the user choose
+    pub fn tick_reward(&mut self, state: &AppState, chosen_file: Option<usize>) -> TickResponse {
         let no_action_response = TickResponse { points_spent: 0.0 };
         match self.spending_protocol {
             RewardPointTransferProtocol::HourlyTransfer(starting_date) => {
                 match starting_date {
-                    Some(date) => {
+                    Some(_) => {
                         if self.reward_type.deactivate_reward(state).is_none() {
                             return no_action_response;
                         };
+                        let points_spent = self.accrued_cost(Local::now());
                         self.spending_protocol = RewardPointTransferProtocol::HourlyTransfer(None);
-                        return TickResponse {
-                            points_spent: (Local::now().signed_duration_since(date).num_minutes()
-                                as f64)
-                                * (self.cost / 60.0),
-                        };
+                        return TickResponse { points_spent };
                     }
                     None => {
-                        if self.reward_type.activate_reward(state).is_none() {
+                        if !self.check_availability(state) {
+                            return no_action_response;
+                        }
+                        let spendable_points = state.balance(&self.currency) + state.settings.overdraft_limit;
+                        let affordable_minutes = (spendable_points / (self.cost / 60.0)).floor();
+                        if self.cost > 0.0 && affordable_minutes < 1.0 {
+                            println!("Not enough {} to rent {} even for a minute!", self.currency, self.name);
+                            return no_action_response;
+                        }
+                        if self.cost > 0.0 {
+                            println!(
+                                "Current balance covers {} of {}",
+                                format_duration(Duration::minutes(affordable_minutes as i64)),
+                                self.name
+                            );
+                        }
+                        if self.reward_type.activate_reward(state, chosen_file).is_none() {
                             return no_action_response;
                         };
+                        self.limits.register_purchase(Local::now());
                         self.spending_protocol = RewardPointTransferProtocol::HourlyTransfer(Some(Local::now()));
                         return TickResponse { points_spent: 0.0 };
                     }
                 }
             }
             RewardPointTransferProtocol::SingularTransfer => {
-                if self.reward_type.execute_reward(state).is_none() {
+                if !self.check_availability(state) {
+                    return no_action_response;
+                }
+                if state.balance(&self.currency) - self.cost < -state.settings.overdraft_limit {
+                    println!(
+                        "Not enough {} to buy {}: it costs {}, but only {} can be spent",
+                        self.currency,
+                        self.name,
+                        self.cost,
+                        state.balance(&self.currency) + state.settings.overdraft_limit
+                    );
+                    return no_action_response;
+                }
+                if self.reward_type.execute_reward(state, chosen_file).is_none() {
                     return no_action_response;
                 };
+                self.limits.register_purchase(Local::now());
                 return TickResponse {
                     points_spent: self.cost,
                 };
@@ -181,6 +534,48 @@ impl RewardCollection {
         }
     }
 
+    pub fn rented_since(&self) -> Option<DateTime<Local>> {
+        return match self.spending_protocol {
+            RewardPointTransferProtocol::HourlyTransfer(starting_date) => starting_date,
+            RewardPointTransferProtocol::SingularTransfer => None,
+        };
+    }
+
+    // What returning the rented reward at `now` would cost
+    pub fn accrued_cost(&self, now: DateTime<Local>) -> f64 {
+        return match self.rented_since() {
+            Some(date) => (now.signed_duration_since(date).num_minutes() as f64) * (self.cost / 60.0),
+            None => 0.0,
+        };
+    }
+
+    fn check_availability(&self, state: &AppState) -> bool {
+        if state.is_over_debt_ceiling(&self.currency) {
+            println!(
+                "Debt of {} is over the ceiling, pay it off before buying {}!",
+                format_amount(-state.balance(&self.currency), &self.currency),
+                self.name
+            );
+            return false;
+        }
+        let now = Local::now();
+        return match self.limits.availability(now) {
+            Availability::Available => true,
+            Availability::OutOfStock => {
+                println!("Reward {} is out of stock!", self.name);
+                false
+            }
+            Availability::AvailableAt(time) => {
+                println!(
+                    "Reward {} can be bought again in {}",
+                    self.name,
+                    format_duration(time.signed_duration_since(now))
+                );
+                false
+            }
+        };
+    }
+
     pub fn validate(&mut self) {
         match &mut self.reward_type {
             RewardType::DecodeFiles(decode_files_reward) => {
@@ -224,11 +619,11 @@ impl Display for RewardCollection {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         let reward_system_description = match self.spending_protocol {
             RewardPointTransferProtocol::SingularTransfer => {
-                format!("One-time payment ({})", self.cost)
+                format!("One-time payment ({})", format_amount(self.cost, &self.currency))
             }
             RewardPointTransferProtocol::HourlyTransfer(starting_date) => format!(
                 "Per-hour payment ({}), {}",
-                self.cost,
+                format_amount(self.cost, &self.currency),
                 if starting_date.is_some() {
                     "started"
                 } else {
@@ -236,19 +631,144 @@ impl Display for RewardCollection {
                 }
             ),
         };
-        return write!(
+        write!(
             f,
             "name: {}\ndescription: {}\nreward system description: {}\ntype:{}",
             self.name, self.description, reward_system_description, self.reward_type
-        );
+        )?;
+        if !self.limits.is_unlimited() {
+            write!(f, "\nlimits: {}", self.limits)?;
+        }
+        return Ok(());
     }
 }
 
 impl Display for RewardType {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         let type_description = match self {
+            RewardType::DecodeFiles(DecodeFilesReward {
+                selection_mode: FileSelectionMode::Mystery(_),
+                ..
+            }) => "Decode random files (mystery)",
             RewardType::DecodeFiles(_) => "Decode files",
         };
         return write!(f, "{}", type_description);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Local, TimeZone};
+
+    use super::{
+        Availability, DecodeFilesReward, FileSelectionMode, MysteryConfig, PeriodCap, PurchaseLimits, RarityTier,
+        SingularFileToDecode,
+    };
+    use crate::time_utils::CalendarPeriod;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        return Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap();
+    }
+
+    fn available_at(limits: &PurchaseLimits, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        return match limits.availability(now) {
+            Availability::Available => Some(now),
+            Availability::AvailableAt(time) => Some(time),
+            Availability::OutOfStock => None,
+        };
+    }
+
+    fn config(streak_bonus: f64) -> MysteryConfig {
+        let tier = |name: &str, weight: f64| RarityTier {
+            name: name.to_string(),
+            weight,
+        };
+        return MysteryConfig {
+            rarity_tiers: vec![tier("common", 6.0), tier("rare", 3.0), tier("legendary", 0.0)],
+            streak_bonus,
+        };
+    }
+
+    fn files(rarities: &[Option<&str>]) -> DecodeFilesReward {
+        return DecodeFilesReward {
+            files_to_decode: rarities
+                .iter()
+                .enumerate()
+                .map(|(i, rarity)| SingularFileToDecode {
+                    path_before_encoding: f!("file{}", i),
+                    reward_name: f!("reward {}", i),
+                    path_after_encoding: f!("encoded{}", i),
+                    rarity: rarity.map(str::to_string),
+                })
+                .collect(),
+            currently_decoded_file_index: None,
+            selection_mode: FileSelectionMode::Chosen,
+        };
+    }
+
+    #[test]
+    fn cooldowns_start_at_the_last_purchase() {
+        let mut limits = PurchaseLimits {
+            cooldown_minutes: Some(90),
+            ..PurchaseLimits::default()
+        };
+        assert_eq!(available_at(&limits, at(12, 9)), Some(at(12, 9)));
+        limits.register_purchase(at(12, 9));
+        assert_eq!(available_at(&limits, at(12, 10)), Some(at(12, 10) + Duration::minutes(30)));
+        assert_eq!(available_at(&limits, at(12, 11)), Some(at(12, 11)));
+    }
+
+    #[test]
+    fn period_caps_wait_for_the_next_period() {
+        let mut limits = PurchaseLimits {
+            period_cap: Some(PeriodCap {
+                period: CalendarPeriod::Week,
+                max_purchases: 2,
+            }),
+            ..PurchaseLimits::default()
+        };
+        limits.register_purchase(at(12, 9));
+        assert_eq!(available_at(&limits, at(13, 9)), Some(at(13, 9)));
+        limits.register_purchase(at(13, 9));
+        assert_eq!(available_at(&limits, at(14, 9)), Some(at(19, 0)));
+        assert_eq!(available_at(&limits, at(19, 9)), Some(at(19, 9)));
+        limits.register_purchase(at(19, 9));
+        assert_eq!(limits.purchase_times, vec![at(19, 9)]);
+    }
+
+    #[test]
+    fn stock_runs_out() {
+        let mut limits = PurchaseLimits {
+            stock: Some(1),
+            ..PurchaseLimits::default()
+        };
+        assert!(!limits.is_unlimited());
+        limits.register_purchase(at(12, 9));
+        assert_eq!(available_at(&limits, at(12, 10)), None);
+        assert!(PurchaseLimits::default().is_unlimited());
+    }
+
+    #[test]
+    fn streaks_make_rarer_tiers_more_likely() {
+        let config = config(0.5);
+        assert_eq!(config.tier_weight(0, 4), 6.0);
+        assert_eq!(config.tier_weight(1, 0), 3.0);
+        assert_eq!(config.tier_weight(1, 2), 6.0);
+        assert_eq!(config.tier_weight(5, 0), 1.0);
+    }
+
+    #[test]
+    fn tier_weight_is_split_between_its_files() {
+        let reward = files(&[Some("common"), Some("common"), Some("rare"), None, Some("unknown")]);
+        assert_eq!(reward.file_weights(&config(0.0), 0), vec![1.5, 1.5, 3.0, 1.5, 1.5]);
+    }
+
+    #[test]
+    fn files_without_weight_are_never_rolled() {
+        let reward = files(&[Some("legendary"), Some("rare")]);
+        assert!((0..100).all(|_| reward.roll_file(&config(0.0), 0) == Some(1)));
+        let all_weightless = files(&[Some("legendary")]);
+        assert_eq!(all_weightless.roll_file(&config(0.0), 0), Some(0));
+        assert_eq!(files(&[]).roll_file(&config(0.0), 0), None);
+    }
+}
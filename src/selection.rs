@@ -0,0 +1,140 @@
+use crate::cli_utils::get_line;
+
+pub enum Selection {
+    Found(usize),
+    Ambiguous(Vec<usize>),
+    NotFound,
+}
+
+// Letters of the key appear in the name in the same order, "wrkt" matches "workout"
+fn is_fuzzy_match(name: &str, key: &str) -> bool {
+    let mut name_chars = name.chars();
+    return key
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|key_char| name_chars.any(|name_char| name_char == key_char));
+}
+
+// Tries a 1-based index, the exact name, a prefix, a substring and a fuzzy match, in that order.
+// A number that is both an index and the name of another entry is ambiguous.
+pub fn select_by_key(names: &[&str], key: &str) -> Selection {
+    let key = key.trim().to_lowercase();
+    if key.is_empty() {
+        return Selection::NotFound;
+    }
+    let lowercase_names: Vec<String> = names.iter().map(|name| name.to_lowercase()).collect();
+    if let Ok(index) = key.parse::<usize>() {
+        if index > 0 && index <= names.len() {
+            let mut candidates = vec![index - 1];
+            candidates.extend(
+                lowercase_names
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, name)| **name == key && *i != index - 1)
+                    .map(|(i, _)| i),
+            );
+            return match candidates.len() {
+                1 => Selection::Found(index - 1),
+                _ => Selection::Ambiguous(candidates),
+            };
+        }
+    }
+    let matchers: [&dyn Fn(&str) -> bool; 4] = [
+        &|name| name == key,
+        &|name| name.starts_with(&key),
+        &|name| name.contains(&key),
+        &|name| is_fuzzy_match(name, &key),
+    ];
+    for matcher in matchers {
+        let matching: Vec<usize> = lowercase_names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| matcher(name))
+            .map(|(i, _)| i)
+            .collect();
+        match matching.len() {
+            0 => continue,
+            1 => return Selection::Found(matching[0]),
+            _ => return Selection::Ambiguous(matching),
+        }
+    }
+    return Selection::NotFound;
+}
+
+// Resolves the key, asking which one was meant when several names match
+pub fn resolve_key(names: &[&str], key: &str) -> Option<usize> {
+    return match select_by_key(names, key) {
+        Selection::Found(index) => Some(index),
+        Selection::Ambiguous(candidates) => choose_candidate(names, &candidates),
+        Selection::NotFound => {
+            println!("Nothing matches {}!", key);
+            None
+        }
+    };
+}
+
+fn choose_candidate(names: &[&str], candidates: &[usize]) -> Option<usize> {
+    println!("Several entries match:");
+    for index in candidates {
+        println!("{}.\t{}", index + 1, names[*index]);
+    }
+    loop {
+        let line = get_line(Some("Enter the index or the name of the one you meant: "))?;
+        let candidate_names: Vec<&str> = candidates.iter().map(|index| names[*index]).collect();
+        let chosen = match line.trim().parse::<usize>() {
+            Ok(index) => candidates.iter().position(|candidate| *candidate + 1 == index),
+            Err(_) => match select_by_key(&candidate_names, &line) {
+                Selection::Found(position) => Some(position),
+                _ => None,
+            },
+        };
+        if let Some(position) = chosen {
+            return Some(candidates[position]);
+        }
+    }
+}
+
+pub fn select_from_cli(prompt: &str, names: &[&str]) -> Option<usize> {
+    loop {
+        let key = get_line(Some(prompt))?;
+        match select_by_key(names, &key) {
+            Selection::Found(index) => return Some(index),
+            Selection::Ambiguous(candidates) => return choose_candidate(names, &candidates),
+            Selection::NotFound => println!("Nothing matches {}!", key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{select_by_key, Selection};
+
+    fn selected(names: &[&str], key: &str) -> Option<usize> {
+        return match select_by_key(names, key) {
+            Selection::Found(index) => Some(index),
+            _ => None,
+        };
+    }
+
+    #[test]
+    fn keys_are_tried_from_exact_to_fuzzy() {
+        let names = ["Workout", "Write report", "Read"];
+        assert_eq!(selected(&names, "2"), Some(1));
+        assert_eq!(selected(&names, "read"), Some(2));
+        assert_eq!(selected(&names, "work"), Some(0));
+        assert_eq!(selected(&names, "report"), Some(1));
+        assert_eq!(selected(&names, "wrkt"), Some(0));
+        assert!(matches!(select_by_key(&names, "w"), Selection::Ambiguous(candidates) if candidates == vec![0, 1]));
+        assert!(matches!(select_by_key(&names, "xyz"), Selection::NotFound));
+        assert!(matches!(select_by_key(&names, " "), Selection::NotFound));
+    }
+
+    #[test]
+    fn numeric_names_can_be_selected() {
+        let names = ["2024", "taxes", "1"];
+        assert_eq!(selected(&names, "2024"), Some(0));
+        assert_eq!(selected(&names, "2"), Some(1));
+        assert!(matches!(select_by_key(&names, "1"), Selection::Ambiguous(candidates) if candidates == vec![0, 2]));
+        assert_eq!(selected(&names, "3"), Some(2));
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::streak::{format_streak_multipliers, parse_streak_multipliers, StreakMultiplier};
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // How far below zero a single purchase may take the balance
+    pub overdraft_limit: f64,
+    // Daily interest on negative balances, 0.01 means 1% per day
+    pub debt_interest_rate: f64,
+    // No purchases are allowed while the debt is larger than this
+    pub debt_ceiling: Option<f64>,
+    // Payouts for tasks completed after their deadline halve every this many hours
+    pub late_half_life_hours: f64,
+    // Bonus share of the payout per day a task is completed before its deadline
+    pub early_bonus_per_day: f64,
+    pub max_early_bonus: f64,
+    // Payout multipliers for recurring tasks, by the minimal streak length they need
+    pub streak_multipliers: Vec<StreakMultiplier>,
+    // Whether hourly sessions flagged as idle are still paid. The work processes are only sampled
+    // while te, re or ui is open and when a session ends, so a session is judged on those moments.
+    pub pay_idle_sessions: bool,
+    // Line printed by the status command, {tasks} and {rewards} list the running timers
+    pub status_template: String,
+    // Format of one running task or rental, with {name}, {elapsed} and {amount}
+    pub status_task_format: String,
+    pub status_reward_format: String,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        return Settings {
+            overdraft_limit: 0.0,
+            debt_interest_rate: 0.0,
+            debt_ceiling: None,
+            late_half_life_hours: 24.0,
+            early_bonus_per_day: 0.02,
+            max_early_bonus: 0.1,
+            streak_multipliers: vec![],
+            pay_idle_sessions: true,
+            status_template: "{balance} {tasks} {rewards}".to_string(),
+            status_task_format: "[{name} {elapsed} +{amount}]".to_string(),
+            status_reward_format: "[{name} {elapsed} -{amount}]".to_string(),
+        };
+    }
+}
+
+impl Settings {
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "overdraft_limit" => self.overdraft_limit = parse_non_negative(value)?,
+            "debt_interest_rate" => self.debt_interest_rate = parse_non_negative(value)?,
+            "debt_ceiling" => self.debt_ceiling = parse_optional_non_negative(value)?,
+            "late_half_life_hours" => self.late_half_life_hours = parse_positive(value)?,
+            "early_bonus_per_day" => self.early_bonus_per_day = parse_non_negative(value)?,
+            "max_early_bonus" => self.max_early_bonus = parse_non_negative(value)?,
+            "pay_idle_sessions" => {
+                self.pay_idle_sessions = value.parse().map_err(|_| f!("{} is not true or false", value))?
+            }
+            "streak_multipliers" => {
+                self.streak_multipliers = parse_streak_multipliers(value)
+                    .ok_or(f!("{} is not a list like 3:1.1,7:1.25", value))?
+            }
+            "status_template" => self.status_template = value.to_string(),
+            "status_task_format" => self.status_task_format = value.to_string(),
+            "status_reward_format" => self.status_reward_format = value.to_string(),
+            _ => return Err(f!("Unknown setting {}", key)),
+        };
+        return Ok(());
+    }
+}
+
+fn parse_non_negative(value: &str) -> Result<f64, String> {
+    return match value.parse::<f64>() {
+        Ok(parsed) if parsed >= 0.0 => Ok(parsed),
+        _ => Err(f!("{} is not a non-negative number", value)),
+    };
+}
+
+fn parse_positive(value: &str) -> Result<f64, String> {
+    return match value.parse::<f64>() {
+        Ok(parsed) if parsed > 0.0 => Ok(parsed),
+        _ => Err(f!("{} is not a positive number", value)),
+    };
+}
+
+fn parse_optional_non_negative(value: &str) -> Result<Option<f64>, String> {
+    return match value {
+        "none" => Ok(None),
+        _ => parse_non_negative(value).map(Some),
+    };
+}
+
+fn format_optional(value: Option<f64>) -> String {
+    return match value {
+        Some(value) => value.to_string(),
+        None => "none".to_string(),
+    };
+}
+
+impl Display for Settings {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        return write!(
+            f,
+            "overdraft_limit: {}\ndebt_interest_rate: {}\ndebt_ceiling: {}\nlate_half_life_hours: {}\nearly_bonus_per_day: {}\nmax_early_bonus: {}\nstreak_multipliers: {}\npay_idle_sessions: {}\nstatus_template: {}\nstatus_task_format: {}\nstatus_reward_format: {}",
+            self.overdraft_limit,
+            self.debt_interest_rate,
+            format_optional(self.debt_ceiling),
+            self.late_half_life_hours,
+            self.early_bonus_per_day,
+            self.max_early_bonus,
+            format_streak_multipliers(&self.streak_multipliers),
+            self.pay_idle_sessions,
+            self.status_template,
+            self.status_task_format,
+            self.status_reward_format
+        );
+    }
+}
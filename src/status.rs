@@ -0,0 +1,105 @@
+use chrono::{DateTime, Local};
+
+use crate::app_state::AppState;
+use crate::currency::format_amount;
+use crate::time_utils::format_duration;
+
+// Status bars have little room, so amounts are cut to two decimals
+fn compact_amount(amount: f64, currency: &str) -> String {
+    return format_amount((amount * 100.0).round() / 100.0, currency);
+}
+
+fn format_timer(format: &str, name: &str, start: DateTime<Local>, amount: String, now: DateTime<Local>) -> String {
+    return format
+        .replace("{name}", name)
+        .replace("{elapsed}", &format_duration(now.signed_duration_since(start)))
+        .replace("{amount}", &amount);
+}
+
+// Fills the template in; placeholders with nothing to show leave no extra spaces behind
+pub fn format_status(state: &AppState, template: &str, now: DateTime<Local>) -> String {
+    let tasks: Vec<String> = state
+        .tasks
+        .iter()
+        .filter_map(|task| {
+            let start = task.running_since()?;
+            let earned = compact_amount(task.accrued_reward(now, &state.settings), &task.currency);
+            Some(format_timer(&state.settings.status_task_format, &task.name, start, earned, now))
+        })
+        .collect();
+    let rewards: Vec<String> = state
+        .rewards
+        .iter()
+        .filter_map(|reward| {
+            let start = reward.rented_since()?;
+            let cost = compact_amount(reward.accrued_cost(now), &reward.currency);
+            Some(format_timer(&state.settings.status_reward_format, &reward.name, start, cost, now))
+        })
+        .collect();
+    let balance = if state.balances.is_empty() {
+        state.format_balances()
+    } else {
+        state
+            .balances
+            .iter()
+            .map(|(currency, amount)| compact_amount(*amount, currency))
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+    let status = template
+        .replace("{balance}", &balance)
+        .replace("{tasks}", &tasks.join(" "))
+        .replace("{rewards}", &rewards.join(" "));
+    return status.split_whitespace().collect::<Vec<&str>>().join(" ");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Local, TimeZone};
+    use serde_json::json;
+
+    use super::format_status;
+    use crate::app_state::AppState;
+
+    fn now() -> DateTime<Local> {
+        return Local.with_ymd_and_hms(2026, 10, 12, 9, 0, 0).unwrap();
+    }
+
+    fn state(tasks: serde_json::Value, rewards: serde_json::Value) -> AppState {
+        return serde_json::from_value(json!({
+            "balances": { "points": 12.345 },
+            "tasks": tasks,
+            "rewards": rewards,
+            "folder_pool": [],
+        }))
+        .unwrap();
+    }
+
+    #[test]
+    fn running_timers_are_listed_with_their_amounts() {
+        let state = state(
+            json!([{
+                "is_regular": true,
+                "reward_system": { "HourlyTransfer": now() - Duration::minutes(90) },
+                "reward": 2.0,
+                "description": "",
+                "name": "write",
+            }]),
+            json!([{
+                "name": "movies",
+                "description": "",
+                "reward_type": { "DecodeFiles": { "files_to_decode": [], "currently_decoded_file_index": null } },
+                "cost": 1.0,
+                "spending_protocol": { "HourlyTransfer": now() - Duration::minutes(30) },
+            }]),
+        );
+        let status = format_status(&state, &state.settings.status_template, now());
+        assert_eq!(status, "12.35 points [write 1h 30m +3 points] [movies 30m -0.5 points]");
+    }
+
+    #[test]
+    fn empty_placeholders_leave_no_gaps() {
+        let state = state(json!([]), json!([]));
+        assert_eq!(format_status(&state, "  {tasks} {balance}  {rewards} ", now()), "12.35 points");
+    }
+}
@@ -0,0 +1,149 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Streak {
+    pub current: u32,
+    pub best: u32,
+    pub last_completion_date: Option<NaiveDate>,
+}
+
+// Maps a date to the start of the period it belongs to
+fn daily_period(date: NaiveDate) -> Option<NaiveDate> {
+    return Some(date);
+}
+
+impl Streak {
+    // Counts consecutive days with at least one completion
+    pub fn register_completion(&mut self, date: NaiveDate) {
+        self.register_period_completion(date, daily_period);
+    }
+
+    // The streak is still alive today if the last completion was today or yesterday
+    pub fn active_streak(&self, today: NaiveDate) -> u32 {
+        return self.active_period_streak(today, daily_period);
+    }
+
+    // Counts consecutive periods with at least one completion
+    pub fn register_period_completion<F>(&mut self, date: NaiveDate, period_of: F)
+    where
+        F: Fn(NaiveDate) -> Option<NaiveDate>,
+    {
+        let current_period = period_of(date);
+        let last_period = self.last_completion_date.and_then(&period_of);
+        self.current = if last_period.is_some() && last_period == current_period {
+            self.current
+        } else if last_period.is_some() && last_period == previous_period(current_period, &period_of) {
+            self.current + 1
+        } else {
+            1
+        };
+        self.best = self.best.max(self.current);
+        self.last_completion_date = Some(date);
+    }
+
+    // The streak is still alive if the last completion was in this period or the previous one
+    pub fn active_period_streak<F>(&self, today: NaiveDate, period_of: F) -> u32
+    where
+        F: Fn(NaiveDate) -> Option<NaiveDate>,
+    {
+        let last_period = match self.last_completion_date.and_then(&period_of) {
+            Some(last_period) => Some(last_period),
+            None => return 0,
+        };
+        let current_period = period_of(today);
+        return if last_period == current_period
+            || last_period == previous_period(current_period, &period_of)
+        {
+            self.current
+        } else {
+            0
+        };
+    }
+}
+
+fn previous_period<F>(period: Option<NaiveDate>, period_of: &F) -> Option<NaiveDate>
+where
+    F: Fn(NaiveDate) -> Option<NaiveDate>,
+{
+    return period.and_then(|period_start| period_of(period_start - Duration::days(1)));
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StreakMultiplier {
+    pub min_streak: u32,
+    pub multiplier: f64,
+}
+
+// Picks the multiplier of the longest streak threshold reached
+pub fn streak_multiplier(table: &[StreakMultiplier], streak: u32) -> f64 {
+    return table
+        .iter()
+        .filter(|entry| entry.min_streak <= streak)
+        .max_by_key(|entry| entry.min_streak)
+        .map(|entry| entry.multiplier)
+        .unwrap_or(1.0);
+}
+
+// Parses tables written as "3:1.1,7:1.25", or "none" for an empty table
+pub fn parse_streak_multipliers(value: &str) -> Option<Vec<StreakMultiplier>> {
+    if value == "none" {
+        return Some(vec![]);
+    }
+    return value
+        .split(',')
+        .map(|entry| {
+            let (min_streak, multiplier) = entry.split_once(':')?;
+            return Some(StreakMultiplier {
+                min_streak: min_streak.trim().parse().ok()?,
+                multiplier: multiplier.trim().parse().ok()?,
+            });
+        })
+        .collect();
+}
+
+pub fn format_streak_multipliers(table: &[StreakMultiplier]) -> String {
+    if table.is_empty() {
+        return "none".to_string();
+    }
+    return table
+        .iter()
+        .map(|entry| format!("{}:{}", entry.min_streak, entry.multiplier))
+        .collect::<Vec<String>>()
+        .join(",");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{format_streak_multipliers, parse_streak_multipliers, streak_multiplier, Streak};
+
+    fn date(day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+    }
+
+    #[test]
+    fn consecutive_days_extend_the_streak() {
+        let mut streak = Streak::default();
+        streak.register_completion(date(5));
+        streak.register_completion(date(5));
+        streak.register_completion(date(6));
+        assert_eq!((streak.current, streak.best), (2, 2));
+        assert_eq!(streak.active_streak(date(7)), 2);
+        assert_eq!(streak.active_streak(date(8)), 0);
+        streak.register_completion(date(9));
+        assert_eq!((streak.current, streak.best), (1, 2));
+    }
+
+    #[test]
+    fn the_longest_reached_threshold_sets_the_multiplier() {
+        let table = parse_streak_multipliers("7:1.25, 3:1.1").unwrap();
+        assert_eq!(streak_multiplier(&table, 2), 1.0);
+        assert_eq!(streak_multiplier(&table, 3), 1.1);
+        assert_eq!(streak_multiplier(&table, 30), 1.25);
+        assert_eq!(format_streak_multipliers(&table), "7:1.25,3:1.1");
+        assert!(parse_streak_multipliers("none").unwrap().is_empty());
+        assert!(parse_streak_multipliers("3-1.1").is_none());
+    }
+}
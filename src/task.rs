@@ -1,115 +1,922 @@
 use chrono;
 use chrono::DateTime;
 use chrono::Local;
-use std::fs;
-use std::path::Path;
 
 use crate::cli_utils::{
-    self, get_line, get_line_with_condition, get_parsed_line, get_parsed_line_with_condition,
+    get_line, get_line_with_condition, get_parsed_line, get_parsed_line_with_condition,
 };
+use crate::app_state::{processes_running, AppState};
+use crate::checklist::{choose_item, completion_share, done_count, init_checklist_from_cli, ChecklistItem};
+use crate::currency::{default_currency, format_amount, get_currency_from_cli};
+use crate::deadline::Deadline;
+use crate::recurrence::{Recurrence, RecurrenceRule, ScheduleStatus};
+use crate::settings::Settings;
+use crate::streak::{streak_multiplier, Streak};
+use crate::time_utils::{format_duration, parse_date_time, DATE_TIME_FORMAT};
+use crate::work_log::{cap_duration, hours_in, WorkLog};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter, Result};
+use sysinfo::System;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Task {
     pub is_regular: bool,
     pub reward_system: RewardPointTransferProtocol,
     pub reward: f64,
+    #[serde(default = "default_currency")]
+    pub currency: String,
     pub description: String,
     pub name: String,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default)]
+    pub deadline: Option<Deadline>,
+    #[serde(default)]
+    pub streak: Streak,
+    #[serde(default)]
+    pub work_log: WorkLog,
+    #[serde(default)]
+    pub max_session_minutes: Option<i64>,
+    // Sessions ending while none of these processes run are flagged as idle
+    #[serde(default)]
+    pub work_processes: Vec<String>,
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    // Names of the tasks that have to be completed before this one pays out
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    // Lifetime totals, kept for the archive
+    #[serde(default)]
+    pub points_earned: f64,
+    #[serde(default)]
+    pub logged_seconds: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+    Urgent,
+}
+
+impl Default for Priority {
+    fn default() -> Priority {
+        return Priority::Normal;
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    fn from_str(line: &str) -> std::result::Result<Priority, String> {
+        return match line.trim().to_lowercase().as_str() {
+            "low" | "1" => Ok(Priority::Low),
+            "normal" | "2" => Ok(Priority::Normal),
+            "high" | "3" => Ok(Priority::High),
+            "urgent" | "4" => Ok(Priority::Urgent),
+            _ => Err(f!("{} is not a priority (low, normal, high or urgent)", line)),
+        };
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let name = match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+            Priority::Urgent => "urgent",
+        };
+        return write!(f, "{}", name);
+    }
 }
 
 impl Task {
-    pub fn init_task_from_cli() -> Task {
+    pub fn init_task_from_cli() -> Option<Task> {
         chrono::Local::now();
-        let name = get_line(Some("Enter the name of the task: "));
-        let description = get_line(Some("Enter the description of the task: "));
-        let is_task_regular = get_line_with_condition(Some("Is the task regular?(y/n)"), |line| {
-            line.to_lowercase() == "y" || line.to_lowercase() == "n"
-        })
-        .to_lowercase()
-            == "y";
+        let name = get_line(Some("Enter the name of the task: "))?;
+        let description = get_line(Some("Enter the description of the task: "))?;
+        let (is_task_regular, recurrence) = init_schedule_from_cli()?;
         let reward_system = match get_parsed_line_with_condition(
             Some("Choose task reward type: \n1 - per hour reward\n2 - per completion reward"),
             |int_val| *int_val == 1 || *int_val == 2,
-        ) {
+        )? {
             1 => RewardPointTransferProtocol::HourlyTransfer(None),
             _ => RewardPointTransferProtocol::SingularTransfer,
         };
-        let reward: f64 = get_parsed_line(Some("Enter the reward amount: "));
-        return Task {
+        let (max_session_minutes, work_processes) = match reward_system {
+            RewardPointTransferProtocol::HourlyTransfer(_) => init_session_limits_from_cli()?,
+            RewardPointTransferProtocol::SingularTransfer => (None, vec![]),
+        };
+        let has_checklist = matches!(reward_system, RewardPointTransferProtocol::SingularTransfer)
+            && get_line_with_condition(Some("Split the task into a checklist?(y/n)"), |line| {
+                line.to_lowercase() == "y" || line.to_lowercase() == "n"
+            })?
+            .to_lowercase()
+                == "y";
+        let checklist = if has_checklist {
+            init_checklist_from_cli()?
+        } else {
+            vec![]
+        };
+        let reward = get_reward_from_cli()?;
+        let currency = get_currency_from_cli()?;
+        let deadline = Deadline::init_deadline_from_cli()?;
+        let depends_on = parse_comma_separated(&get_line(Some(
+            "Enter the names of the tasks this one depends on, comma-separated (empty for none): ",
+        ))?);
+        let tags = parse_comma_separated(&get_line(Some(
+            "Enter the tags of the task, comma-separated (empty for none): ",
+        ))?);
+        let project = init_optional_line("Enter the project of the task (empty for none): ")?;
+        let priority = get_parsed_line(Some("Enter the priority of the task (low, normal, high or urgent): "))?;
+        return Some(Task {
             is_regular: is_task_regular,
             reward_system,
             reward,
+            currency,
             description,
             name,
+            recurrence,
+            deadline,
+            streak: Streak::default(),
+            work_log: WorkLog::default(),
+            max_session_minutes,
+            work_processes,
+            checklist,
+            depends_on,
+            tags,
+            project,
+            priority,
+            points_earned: 0.0,
+            logged_seconds: 0,
+        });
+    }
+
+    // Changes one field at a time, a running session, the work log and the streak are kept
+    pub fn edit_from_cli(&mut self) {
+        loop {
+            let is_hourly = matches!(self.reward_system, RewardPointTransferProtocol::HourlyTransfer(_));
+            let last_field = if is_hourly { "11 - session limits" } else { "11 - checklist" };
+            let field = get_parsed_line_with_condition(
+                Some(&f!(
+                    "Choose field to edit: \n0 - done\n1 - name\n2 - description\n3 - reward\n4 - currency\n5 - schedule\n6 - deadline\n7 - prerequisites\n8 - tags\n9 - project\n10 - priority\n{}",
+                    last_field
+                )),
+                |int_val: &i64| (0..=11).contains(int_val),
+            );
+            match field {
+                None | Some(0) => return,
+                Some(field) => {
+                    if self.edit_field_from_cli(field, is_hourly).is_none() {
+                        println!("Edit cancelled");
+                    }
+                }
+            }
+            println!("{}", self);
+        }
+    }
+
+    fn edit_field_from_cli(&mut self, field: i64, is_hourly: bool) -> Option<()> {
+        match field {
+            1 => {
+                self.name = get_line_with_condition(Some("Enter the name of the task: "), |line| {
+                    !line.trim().is_empty()
+                })?
+            }
+            2 => self.description = get_line(Some("Enter the description of the task: "))?,
+            3 => self.reward = get_reward_from_cli()?,
+            4 => self.currency = get_currency_from_cli()?,
+            5 => {
+                let (is_regular, recurrence) = init_schedule_from_cli()?;
+                // Completions made so far still count towards the current period
+                let previous_completions = self.recurrence.take().map(|old| old.completions);
+                self.is_regular = is_regular;
+                self.recurrence = recurrence.map(|mut recurrence| {
+                    recurrence.completions = previous_completions.unwrap_or_default();
+                    recurrence
+                });
+            }
+            6 => self.deadline = Deadline::init_deadline_from_cli()?,
+            7 => {
+                self.depends_on = parse_comma_separated(&get_line(Some(
+                    "Enter the names of the tasks this one depends on, comma-separated (empty for none): ",
+                ))?)
+            }
+            8 => {
+                self.tags = parse_comma_separated(&get_line(Some(
+                    "Enter the tags of the task, comma-separated (empty for none): ",
+                ))?)
+            }
+            9 => self.project = init_optional_line("Enter the project of the task (empty for none): ")?,
+            10 => {
+                self.priority =
+                    get_parsed_line(Some("Enter the priority of the task (low, normal, high or urgent): "))?
+            }
+            _ if is_hourly => {
+                let (max_session_minutes, work_processes) = init_session_limits_from_cli()?;
+                self.max_session_minutes = max_session_minutes;
+                self.work_processes = work_processes;
+            }
+            _ => {
+                // Items that keep their name stay checked off
+                let mut checklist = init_checklist_from_cli()?;
+                for item in &mut checklist {
+                    item.done = self
+                        .checklist
+                        .iter()
+                        .any(|previous| previous.name == item.name && previous.done);
+                }
+                self.checklist = checklist;
+            }
+        }
+        return Some(());
+    }
+
+    // Regular tasks without a schedule are never done, they keep blocking their dependents
+    pub fn is_completed(&self) -> bool {
+        return match &self.recurrence {
+            Some(recurrence) => matches!(recurrence.status(Local::now()), ScheduleStatus::Done),
+            None => false,
+        };
+    }
+
+    // Prerequisites are met once they are done for the period or gone from the task list
+    pub fn remaining_prerequisites(&self, tasks: &[Task]) -> Vec<String> {
+        return self
+            .depends_on
+            .iter()
+            .filter(|name| {
+                tasks
+                    .iter()
+                    .any(|task| &task.name == *name && !task.is_completed())
+            })
+            .cloned()
+            .collect();
+    }
+
+    // Whether following the prerequisites from this task leads back to it, which would block it forever
+    pub fn has_dependency_cycle(&self, tasks: &[Task]) -> bool {
+        let mut to_visit: Vec<&String> = self.depends_on.iter().collect();
+        let mut visited: Vec<&String> = vec![];
+        while let Some(name) = to_visit.pop() {
+            if *name == self.name {
+                return true;
+            }
+            if visited.contains(&name) {
+                continue;
+            }
+            visited.push(name);
+            to_visit.extend(
+                tasks
+                    .iter()
+                    .filter(|task| &task.name == name)
+                    .flat_map(|task| task.depends_on.iter()),
+            );
+        }
+        return false;
+    }
+
+    fn check_schedule(&self, now: DateTime<Local>) -> bool {
+        return match &self.recurrence {
+            Some(recurrence) if !recurrence.can_complete(now) => {
+                println!("Task {} can not be completed now: {}", self.name, recurrence.status(now));
+                false
+            }
+            _ => true,
         };
     }
-    pub fn tick_task(&mut self) -> TickResponse {
+
+    // Returns the payout multiplier earned by the streak of the recurring task
+    fn register_completion(&mut self, now: DateTime<Local>, state: &AppState) -> f64 {
+        if let Some(deadline) = &mut self.deadline {
+            deadline.mark_met(now);
+        }
+        let recurrence = match &mut self.recurrence {
+            Some(recurrence) => recurrence,
+            None => return 1.0,
+        };
+        recurrence.register_completion(now);
+        self.streak
+            .register_period_completion(now.date_naive(), |date| recurrence.period_start(date));
+        let multiplier = streak_multiplier(&state.settings.streak_multipliers, self.streak.current);
+        if (multiplier - 1.0).abs() > f64::EPSILON {
+            println!(
+                "Streak of {} for {}: reward multiplied by {}",
+                self.streak.current, self.name, multiplier
+            );
+        }
+        return multiplier;
+    }
+
+    pub fn active_streak(&self) -> u32 {
+        return match &self.recurrence {
+            Some(recurrence) => self
+                .streak
+                .active_period_streak(Local::now().date_naive(), |date| recurrence.period_start(date)),
+            None => 0,
+        };
+    }
+
+    fn deadline_adjusted_reward(&self, now: DateTime<Local>, state: &AppState) -> f64 {
+        return match &self.deadline {
+            Some(deadline) => {
+                let multiplier = deadline.payout_multiplier(now, &state.settings);
+                if (multiplier - 1.0).abs() > f64::EPSILON {
+                    println!(
+                        "Reward for {} is scaled by {:.2} because of its deadline",
+                        self.name, multiplier
+                    );
+                }
+                self.reward * multiplier
+            }
+            None => self.reward,
+        };
+    }
+
+    pub fn tick_task(&mut self, state: &AppState) -> TickResponse {
+        let now = Local::now();
+        let no_action_response = TickResponse {
+            task_is_to_be_removed: false,
+            reward_acquired: 0.0,
+            task_completed: false,
+        };
+        // Blocked tasks can not be started or completed, but a running session can always be stopped
+        let remaining_prerequisites = self.remaining_prerequisites(&state.tasks);
+        let is_blocked = !remaining_prerequisites.is_empty();
+        if is_blocked {
+            println!(
+                "Task {} is blocked until these are completed: {}",
+                self.name,
+                remaining_prerequisites.join(", ")
+            );
+        }
         match self.reward_system {
             RewardPointTransferProtocol::HourlyTransfer(starting_date) => {
-                self.reward_system =
-                    RewardPointTransferProtocol::HourlyTransfer(if starting_date.is_some() {
-                        None
-                    } else {
-                        Some(Local::now())
-                    });
                 match starting_date {
                     Some(date) => {
-                        return TickResponse {
-                            task_is_to_be_removed: !self.is_regular,
-                            reward_acquired: (Local::now().signed_duration_since(date).num_minutes()
-                                as f64)
-                                * (self.reward / 60.0),
+                        self.close_running_session(date, now, state);
+                        if is_blocked {
+                            self.reward_system = RewardPointTransferProtocol::HourlyTransfer(None);
+                            println!("Stopped the session of {}, it is paid once the task is unblocked", self.name);
+                            return no_action_response;
                         }
                     }
-                    None => {
-                        return TickResponse {
-                            task_is_to_be_removed: false,
-                            reward_acquired: 0.0,
+                    _ if is_blocked => return no_action_response,
+                    None if self.work_log.is_empty() => {
+                        if !self.check_schedule(now) {
+                            return no_action_response;
                         }
+                        self.reward_system = RewardPointTransferProtocol::HourlyTransfer(Some(now));
+                        return no_action_response;
                     }
+                    // A paused task is finished and paid for the sessions logged so far
+                    None => (),
                 }
+                self.reward_system = RewardPointTransferProtocol::HourlyTransfer(None);
+                let worked = self.payable_duration(state);
+                self.logged_seconds += worked.num_seconds();
+                self.work_log.clear();
+                let streak_multiplier = self.register_completion(now, state);
+                return TickResponse {
+                    task_is_to_be_removed: !self.is_regular,
+                    reward_acquired: hours_in(worked) * self.reward * streak_multiplier,
+                    task_completed: true,
+                };
             }
             RewardPointTransferProtocol::SingularTransfer => {
+                if is_blocked || !self.check_schedule(now) {
+                    return no_action_response;
+                }
+                if !self.checklist.is_empty() {
+                    return self.tick_checklist_item(now, state);
+                }
+                let streak_multiplier = self.register_completion(now, state);
                 return TickResponse {
                     task_is_to_be_removed: !self.is_regular,
-                    reward_acquired: self.reward,
+                    reward_acquired: self.deadline_adjusted_reward(now, state) * streak_multiplier,
+                    task_completed: true,
                 };
             }
         }
     }
+
+    pub fn is_paused(&self) -> bool {
+        return matches!(self.reward_system, RewardPointTransferProtocol::HourlyTransfer(None))
+            && !self.work_log.is_empty();
+    }
+
+    // Pays the share of the checked item, and the rest of the reward once all items are done
+    fn tick_checklist_item(&mut self, now: DateTime<Local>, state: &AppState) -> TickResponse {
+        let item_index = match choose_item(&self.checklist) {
+            Some(item_index) => item_index,
+            None => {
+                return TickResponse {
+                    task_is_to_be_removed: false,
+                    reward_acquired: 0.0,
+                    task_completed: false,
+                }
+            }
+        };
+        self.checklist[item_index].done = true;
+        let adjusted_reward = self.deadline_adjusted_reward(now, state);
+        let mut reward_acquired = adjusted_reward * self.checklist[item_index].reward_share;
+        println!(
+            "Checked off {} ({}/{})",
+            self.checklist[item_index].name,
+            done_count(&self.checklist),
+            self.checklist.len()
+        );
+        if done_count(&self.checklist) < self.checklist.len() {
+            return TickResponse {
+                task_is_to_be_removed: false,
+                reward_acquired,
+                task_completed: false,
+            };
+        }
+        println!("All items of {} are done!", self.name);
+        let streak_multiplier = self.register_completion(now, state);
+        reward_acquired += adjusted_reward * completion_share(&self.checklist) * streak_multiplier;
+        if self.is_regular {
+            self.checklist.iter_mut().for_each(|item| item.done = false);
+        }
+        return TickResponse {
+            task_is_to_be_removed: !self.is_regular,
+            reward_acquired,
+            task_completed: true,
+        };
+    }
+
+    fn max_session(&self) -> Option<chrono::Duration> {
+        return self.max_session_minutes.map(chrono::Duration::minutes);
+    }
+
+    fn close_running_session(&mut self, start: DateTime<Local>, now: DateTime<Local>, state: &AppState) {
+        let idle_now = if self.work_processes.is_empty() {
+            None
+        } else {
+            Some(!state.is_any_process_running(&self.work_processes))
+        };
+        if self.work_log.close_running_session(start, now, idle_now) {
+            println!(
+                "None of the work processes of {} ran for most of the session, it is flagged as idle",
+                self.name
+            );
+        }
+    }
+
+    // Called while the editing loops and the dashboard are open, at most once a minute
+    pub fn sample_work_processes(&mut self, now: DateTime<Local>, sys: &System) {
+        if let Some(start) = self.running_since() {
+            if !self.work_processes.is_empty() && self.work_log.running_samples.is_due(now) {
+                let idle = !processes_running(sys, &self.work_processes);
+                self.work_log.running_samples.record(start, now, idle);
+            }
+        }
+    }
+
+    fn payable_duration(&self, state: &AppState) -> chrono::Duration {
+        let logged = self.work_log.logged_duration();
+        let payable = self
+            .work_log
+            .payable_duration(self.max_session(), state.settings.pay_idle_sessions);
+        if payable < logged {
+            println!(
+                "Paying for {} of {} logged because of the session length limit or idle sessions",
+                format_duration(payable),
+                format_duration(logged)
+            );
+        }
+        return payable;
+    }
+
+    pub fn running_since(&self) -> Option<DateTime<Local>> {
+        return match self.reward_system {
+            RewardPointTransferProtocol::HourlyTransfer(starting_date) => starting_date,
+            RewardPointTransferProtocol::SingularTransfer => None,
+        };
+    }
+
+    // The streak multiplier a completion at `now` would get
+    fn next_streak_multiplier(&self, now: DateTime<Local>, settings: &Settings) -> f64 {
+        let recurrence = match &self.recurrence {
+            Some(recurrence) => recurrence,
+            None => return 1.0,
+        };
+        let mut streak = self.streak.clone();
+        streak.register_period_completion(now.date_naive(), |date| recurrence.period_start(date));
+        return streak_multiplier(&settings.streak_multipliers, streak.current);
+    }
+
+    // What finishing the per-hour task at `now` would pay
+    pub fn accrued_reward(&self, now: DateTime<Local>, settings: &Settings) -> f64 {
+        if !matches!(self.reward_system, RewardPointTransferProtocol::HourlyTransfer(_)) {
+            return 0.0;
+        }
+        let mut worked = self.work_log.payable_duration(None, settings.pay_idle_sessions);
+        if let Some(start) = self.running_since() {
+            worked = worked + now.signed_duration_since(start);
+        }
+        let payable = cap_duration(worked, self.max_session());
+        return hours_in(payable) * self.reward * self.next_streak_multiplier(now, settings);
+    }
+
+    // When the running session uses up what is left of the session limit and stops earning
+    pub fn session_cap_time(&self, settings: &Settings) -> Option<DateTime<Local>> {
+        let logged = self.work_log.payable_duration(None, settings.pay_idle_sessions);
+        let left = (self.max_session()? - logged).max(chrono::Duration::zero());
+        return Some(self.running_since()? + left);
+    }
+
+    // Points per hour the running session still earns, nothing once it is over the session limit
+    pub fn hourly_rate(&self, now: DateTime<Local>, settings: &Settings) -> f64 {
+        return match (self.running_since(), self.session_cap_time(settings)) {
+            (Some(_), Some(cap_time)) if now >= cap_time => 0.0,
+            (Some(_), _) => self.reward * self.next_streak_multiplier(now, settings),
+            (None, _) => 0.0,
+        };
+    }
+
+    pub fn toggle_pause(&mut self, state: &AppState) {
+        let now = Local::now();
+        match self.reward_system {
+            RewardPointTransferProtocol::HourlyTransfer(Some(date)) => {
+                self.close_running_session(date, now, state);
+                self.reward_system = RewardPointTransferProtocol::HourlyTransfer(None);
+                println!(
+                    "Paused {}, {} logged so far",
+                    self.name,
+                    format_duration(self.work_log.logged_duration())
+                );
+            }
+            RewardPointTransferProtocol::HourlyTransfer(None) if !self.work_log.is_empty() => {
+                self.reward_system = RewardPointTransferProtocol::HourlyTransfer(Some(now));
+                println!("Resumed {}", self.name);
+            }
+            _ => println!("Task {} is not a started per-hour task!", self.name),
+        }
+    }
+
+    pub fn correct_time_from_cli(&mut self) -> Option<()> {
+        let running_start = match self.reward_system {
+            RewardPointTransferProtocol::HourlyTransfer(starting_date) => starting_date,
+            RewardPointTransferProtocol::SingularTransfer => {
+                println!("Task {} is not a per-hour task!", self.name);
+                return Some(());
+            }
+        };
+        let prompt = if running_start.is_some() {
+            "1 - log a past session by start and end\n2 - log a past session by duration, ending now\n3 - change the start of the running session\n4 - cap the running session at a given end"
+        } else {
+            "1 - log a past session by start and end\n2 - log a past session by duration, ending now"
+        };
+        let now = Local::now();
+        match get_parsed_line_with_condition(Some(prompt), |int_val: &i64| {
+            (1..=2).contains(int_val) || ((3..=4).contains(int_val) && running_start.is_some())
+        })? {
+            1 => {
+                let start = get_past_time_from_cli("Enter the start of the session (YYYY-MM-DD HH:MM or HH:MM): ")?;
+                let end = get_past_time_from_cli("Enter the end of the session (YYYY-MM-DD HH:MM or HH:MM): ")?;
+                self.log_session(start, end);
+            }
+            2 => {
+                let minutes: i64 = get_parsed_line_with_condition(
+                    Some("Enter the duration of the session in minutes: "),
+                    |int_val: &i64| *int_val > 0,
+                )?;
+                self.log_session(now - chrono::Duration::minutes(minutes), now);
+            }
+            3 => {
+                let new_start = get_past_time_from_cli("Enter the new start of the running session (YYYY-MM-DD HH:MM or HH:MM): ")?;
+                self.set_running_start(new_start);
+            }
+            _ => {
+                let end = get_past_time_from_cli("Enter the end of the running session (YYYY-MM-DD HH:MM or HH:MM): ")?;
+                self.cap_running_session(end);
+            }
+        }
+        return Some(());
+    }
+
+    pub fn log_session(&mut self, start: DateTime<Local>, end: DateTime<Local>) {
+        if start >= end {
+            println!("The session has to end after it starts!");
+            return;
+        }
+        let is_fresh = matches!(self.reward_system, RewardPointTransferProtocol::HourlyTransfer(None))
+            && self.work_log.is_empty();
+        if is_fresh && !self.check_schedule(Local::now()) {
+            return;
+        }
+        let overlaps_running = match self.running_since() {
+            Some(running_start) => start < Local::now() && running_start < end,
+            None => false,
+        };
+        if overlaps_running || self.work_log.overlaps(start, end) {
+            println!("The session overlaps time that is already logged!");
+            return;
+        }
+        self.work_log.close_session(start, end, false);
+        self.work_log.record_correction(format!(
+            "Logged a session of {} from {} to {}",
+            format_duration(end.signed_duration_since(start)),
+            start.format(DATE_TIME_FORMAT),
+            end.format(DATE_TIME_FORMAT)
+        ));
+    }
+
+    pub fn set_running_start(&mut self, new_start: DateTime<Local>) {
+        if let RewardPointTransferProtocol::HourlyTransfer(Some(old_start)) = self.reward_system {
+            if self.work_log.overlaps(new_start, Local::now()) {
+                println!("The session overlaps time that is already logged!");
+                return;
+            }
+            self.reward_system = RewardPointTransferProtocol::HourlyTransfer(Some(new_start));
+            self.work_log.record_correction(format!(
+                "Moved the start of the running session from {} to {}",
+                old_start.format(DATE_TIME_FORMAT),
+                new_start.format(DATE_TIME_FORMAT)
+            ));
+        }
+    }
+
+    // Ends the running session at the given time, leaving the task paused
+    pub fn cap_running_session(&mut self, end: DateTime<Local>) {
+        if let RewardPointTransferProtocol::HourlyTransfer(Some(start)) = self.reward_system {
+            if end <= start {
+                println!("The session has to end after it starts!");
+                return;
+            }
+            self.work_log.close_running_session(start, end, None);
+            self.reward_system = RewardPointTransferProtocol::HourlyTransfer(None);
+            self.work_log.record_correction(format!(
+                "Capped the session started at {} at {} instead of {}",
+                start.format(DATE_TIME_FORMAT),
+                end.format(DATE_TIME_FORMAT),
+                Local::now().format(DATE_TIME_FORMAT)
+            ));
+        }
+    }
+
+    // Copy of the task with all progress reset, as if it was just created
+    pub fn fresh_copy(&self) -> Task {
+        let mut copy = self.clone();
+        if let RewardPointTransferProtocol::HourlyTransfer(_) = copy.reward_system {
+            copy.reward_system = RewardPointTransferProtocol::HourlyTransfer(None);
+        }
+        if let Some(recurrence) = &mut copy.recurrence {
+            *recurrence = Recurrence::new(recurrence.rule.clone(), recurrence.completions_per_period);
+        }
+        // Deadlines are dates, so they never carry over to the next task
+        copy.deadline = None;
+        copy.checklist.iter_mut().for_each(|item| item.done = false);
+        copy.streak = Streak::default();
+        copy.work_log = WorkLog::default();
+        copy.points_earned = 0.0;
+        copy.logged_seconds = 0;
+        return copy;
+    }
+
+    // One line per task, for listings that would be unreadable with the full description
+    pub fn summary_line(&self) -> String {
+        let mut line = format!("[{}] {}", self.priority, self.name);
+        if let Some(project) = &self.project {
+            line += &format!(" ({})", project);
+        }
+        for tag in &self.tags {
+            line += &format!(" #{}", tag);
+        }
+        let reward_kind = match self.reward_system {
+            RewardPointTransferProtocol::SingularTransfer => "per completion",
+            RewardPointTransferProtocol::HourlyTransfer(_) => "per hour",
+        };
+        line += &format!(" - {} {}", format_amount(self.reward, &self.currency), reward_kind);
+        if let Some(recurrence) = &self.recurrence {
+            line += &format!(", {}", recurrence.status(Local::now()));
+        }
+        return line;
+    }
+}
+
+pub fn parse_comma_separated(line: &str) -> Vec<String> {
+    return line
+        .split(',')
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect();
+}
+
+fn init_schedule_from_cli() -> Option<(bool, Option<Recurrence>)> {
+    let schedule_choice = get_parsed_line_with_condition(
+        Some("Choose task schedule: \n1 - one-off\n2 - regular, without a schedule\n3 - daily\n4 - on weekdays\n5 - every N days\n6 - weekly on given days\n7 - monthly"),
+        |int_val: &i64| (1..=7).contains(int_val),
+    )?;
+    let recurrence = if schedule_choice >= 3 {
+        let rule = RecurrenceRule::init_rule_from_cli(schedule_choice)?;
+        let completions_per_period = get_parsed_line_with_condition(
+            Some("Enter how many times the task can be completed per period: "),
+            |int_val: &u32| *int_val > 0,
+        )?;
+        Some(Recurrence::new(rule, completions_per_period))
+    } else {
+        None
+    };
+    return Some((schedule_choice != 1, recurrence));
+}
+
+// The outer None means the input was cancelled, the inner one that the line was empty
+pub(crate) fn init_optional_line(prompt: &str) -> Option<Option<String>> {
+    let line = get_line(Some(prompt))?;
+    return Some(if line.trim().is_empty() {
+        None
+    } else {
+        Some(line.trim().to_string())
+    });
+}
+
+fn get_reward_from_cli() -> Option<f64> {
+    return get_parsed_line_with_condition(Some("Enter the reward amount: "), |reward: &f64| reward.is_finite());
+}
+
+fn init_session_limits_from_cli() -> Option<(Option<i64>, Vec<String>)> {
+    let max_session_minutes: i64 = get_parsed_line_with_condition(
+        Some("Enter the maximum paid time in minutes, for all sessions until the task is ticked off (0 for none): "),
+        |int_val: &i64| *int_val >= 0,
+    )?;
+    let work_processes = parse_comma_separated(&get_line(Some(
+        "Enter the work processes to watch for idle detection, checked while te, re or ui is open and when a session ends, comma-separated (empty for none): ",
+    ))?);
+    return Some((
+        if max_session_minutes > 0 { Some(max_session_minutes) } else { None },
+        work_processes,
+    ));
+}
+
+fn get_past_time_from_cli(prompt: &str) -> Option<DateTime<Local>> {
+    let line = get_line_with_condition(Some(prompt), |line| match parse_date_time(line) {
+        Some(time) => time <= Local::now(),
+        None => false,
+    })?;
+    return parse_date_time(&line);
 }
 
 pub struct TickResponse {
     pub task_is_to_be_removed: bool,
     pub reward_acquired: f64,
+    // Whether the tick finished the task, rather than starting it or checking off part of it
+    pub task_completed: bool,
 }
 impl Display for Task {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        let reward_system_description = match self.reward_system {
+        let mut reward_system_description = match self.reward_system {
             RewardPointTransferProtocol::SingularTransfer => {
-                format!("Per-completion reward ({})", self.reward)
+                format!("Per-completion reward ({})", format_amount(self.reward, &self.currency))
             }
             RewardPointTransferProtocol::HourlyTransfer(starting_date) => format!(
                 "Per-hour reward ({}), {}",
-                self.reward,
-                if starting_date.is_some() {
-                    "started"
-                } else {
-                    "not started"
+                format_amount(self.reward, &self.currency),
+                match starting_date {
+                    Some(date) => {
+                        let running_for = Local::now().signed_duration_since(date);
+                        let worked = running_for + self.work_log.logged_duration();
+                        format!(
+                            "running for {}{} ({} logged before)",
+                            format_duration(running_for),
+                            if cap_duration(worked, self.max_session()) < worked {
+                                ", over the session limit"
+                            } else {
+                                ""
+                            },
+                            format_duration(self.work_log.logged_duration())
+                        )
+                    }
+                    None if self.is_paused() => format!(
+                        "paused, {} logged",
+                        format_duration(self.work_log.logged_duration())
+                    ),
+                    None => "not started".to_string(),
                 }
             ),
         };
-        return write!(
+        if let Some(max_session_minutes) = self.max_session_minutes {
+            reward_system_description += &format!(
+                ", paid up to {} until ticked off",
+                format_duration(chrono::Duration::minutes(max_session_minutes))
+            );
+        }
+        if !self.work_processes.is_empty() {
+            reward_system_description += &format!(", watching {}", self.work_processes.join(", "));
+        }
+        if self.work_log.idle_session_count() > 0 {
+            reward_system_description +=
+                &format!(", {} idle session(s)", self.work_log.idle_session_count());
+        }
+        if self.recurrence.is_some() {
+            reward_system_description += &format!(
+                ", streak {} (best {})",
+                self.active_streak(),
+                self.streak.best
+            );
+        }
+        write!(
             f,
-            "name: {}\ndescription: {}\nis_regular: {}\nreward system description: {}",
-            self.name, self.description, self.is_regular, reward_system_description
-        );
+            "name: {}\ndescription: {}\nis_regular: {}\nreward system description: {}\npriority: {}",
+            self.name, self.description, self.is_regular, reward_system_description, self.priority
+        )?;
+        if let Some(project) = &self.project {
+            write!(f, "\nproject: {}", project)?;
+        }
+        if !self.tags.is_empty() {
+            write!(f, "\ntags: {}", self.tags.join(", "))?;
+        }
+        if !self.checklist.is_empty() {
+            write!(f, "\nchecklist: {}/{}", done_count(&self.checklist), self.checklist.len())?;
+            for item in &self.checklist {
+                write!(f, "\n    {}", item)?;
+            }
+        }
+        if let Some(recurrence) = &self.recurrence {
+            write!(f, "\nschedule: {}", recurrence)?;
+        }
+        if let Some(deadline) = &self.deadline {
+            write!(f, "\ndeadline: {}", deadline)?;
+        }
+        // Only the latest corrections, older ones are kept in the state file
+        let recent_corrections = self.work_log.corrections.iter().rev().take(3);
+        for correction in recent_corrections {
+            write!(
+                f,
+                "\ncorrection at {}: {}",
+                correction.made_at.format(DATE_TIME_FORMAT),
+                correction.description
+            )?;
+        }
+        return Ok(());
     }
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub enum RewardPointTransferProtocol {
     HourlyTransfer(Option<DateTime<Local>>),
     SingularTransfer,
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Task;
+
+    fn task(name: &str, depends_on: &[&str]) -> Task {
+        return serde_json::from_value(json!({
+            "is_regular": false,
+            "reward_system": "SingularTransfer",
+            "reward": 1.0,
+            "description": "",
+            "name": name,
+            "depends_on": depends_on,
+        }))
+        .unwrap();
+    }
+
+    #[test]
+    fn dependency_cycles_are_found() {
+        let tasks = vec![task("write", &["outline"]), task("outline", &["research"]), task("research", &[])];
+        assert!(!tasks[0].has_dependency_cycle(&tasks));
+        assert!(task("research", &["write"]).has_dependency_cycle(&tasks));
+        assert!(task("proofread", &["proofread"]).has_dependency_cycle(&tasks));
+        assert!(!task("publish", &["write", "outline"]).has_dependency_cycle(&tasks));
+    }
+
+    #[test]
+    fn fresh_copies_start_without_progress_or_deadline() {
+        let mut done = task("write", &[]);
+        done.deadline = serde_json::from_value(json!({
+            "due": "2026-10-01T18:00:00+00:00",
+            "missed_penalty": 5.0,
+            "penalty_applied": false,
+            "met": true,
+        }))
+        .unwrap();
+        done.points_earned = 12.0;
+        done.logged_seconds = 3600;
+        done.streak.current = 4;
+        let copy = done.fresh_copy();
+        assert!(copy.deadline.is_none());
+        assert_eq!((copy.points_earned, copy.logged_seconds, copy.streak.current), (0.0, 0, 0));
+        assert_eq!(copy.name, "write");
+    }
+
+    #[test]
+    fn only_listed_unfinished_tasks_block() {
+        let tasks = vec![task("outline", &[]), task("research", &[])];
+        let write = task("write", &["outline", "gone"]);
+        assert_eq!(write.remaining_prerequisites(&tasks), vec!["outline".to_string()]);
+    }
+}
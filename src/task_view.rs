@@ -0,0 +1,213 @@
+use std::cmp::Ordering;
+
+use crate::cli_utils::get_line_with_condition;
+use crate::task::{init_optional_line, Priority, Task};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TaskSort {
+    Index,
+    Priority,
+    Name,
+    Project,
+    Deadline,
+}
+
+impl std::str::FromStr for TaskSort {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<TaskSort, String> {
+        return match line.trim().to_lowercase().as_str() {
+            "" | "index" => Ok(TaskSort::Index),
+            "priority" => Ok(TaskSort::Priority),
+            "name" => Ok(TaskSort::Name),
+            "project" => Ok(TaskSort::Project),
+            "deadline" => Ok(TaskSort::Deadline),
+            _ => Err(f!(
+                "{} is not a sort order (index, priority, name, project or deadline)",
+                line
+            )),
+        };
+    }
+}
+
+// Filter and sort order of a task listing; indices stay those of the full list
+pub struct TaskView {
+    pub tag: Option<String>,
+    pub project: Option<String>,
+    pub min_priority: Option<Priority>,
+    pub sort: TaskSort,
+}
+
+impl Default for TaskView {
+    fn default() -> TaskView {
+        return TaskView {
+            tag: None,
+            project: None,
+            min_priority: None,
+            sort: TaskSort::Index,
+        };
+    }
+}
+
+impl TaskView {
+    pub fn from_args(args: &[String]) -> Result<TaskView, String> {
+        let mut view = TaskView::default();
+        let mut remaining_args = args.iter();
+        while let Some(flag) = remaining_args.next() {
+            let value = remaining_args
+                .next()
+                .ok_or(f!("Missing value for {}", flag))?;
+            match flag.as_str() {
+                "--tag" => view.tag = Some(value.clone()),
+                "--project" => view.project = Some(value.clone()),
+                "--priority" => view.min_priority = Some(value.parse()?),
+                "--sort" => view.sort = value.parse()?,
+                _ => return Err(f!("Unknown option {}", flag)),
+            }
+        }
+        return Ok(view);
+    }
+
+    pub fn init_view_from_cli() -> Option<TaskView> {
+        let tag = init_optional_line("Show only tasks with the tag (empty for any): ")?;
+        let project = init_optional_line("Show only tasks of the project (empty for any): ")?;
+        // An empty line is the only one that does not parse
+        let min_priority = get_line_with_condition(
+            Some("Show only tasks with at least the priority (empty for any): "),
+            |line| line.trim().is_empty() || line.parse::<Priority>().is_ok(),
+        )?
+        .parse()
+        .ok();
+        let sort = get_line_with_condition(
+            Some("Sort by (index, priority, name, project or deadline; empty for index): "),
+            |line| line.parse::<TaskSort>().is_ok(),
+        )?
+        .parse()
+        .unwrap();
+        return Some(TaskView {
+            tag,
+            project,
+            min_priority,
+            sort,
+        });
+    }
+
+    pub fn is_filtering(&self) -> bool {
+        return self.tag.is_some() || self.project.is_some() || self.min_priority.is_some();
+    }
+
+    fn matches(&self, task: &Task) -> bool {
+        let tag_matches = match &self.tag {
+            Some(tag) => task.tags.contains(tag),
+            None => true,
+        };
+        let project_matches = match &self.project {
+            Some(project) => task.project.as_ref() == Some(project),
+            None => true,
+        };
+        let priority_matches = match self.min_priority {
+            Some(min_priority) => task.priority >= min_priority,
+            None => true,
+        };
+        return tag_matches && project_matches && priority_matches;
+    }
+
+    fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        return match self.sort {
+            TaskSort::Index => Ordering::Equal,
+            TaskSort::Priority => b.priority.cmp(&a.priority),
+            TaskSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            // Tasks without a project or a deadline go last
+            TaskSort::Project => match (&a.project, &b.project) {
+                (Some(a_project), Some(b_project)) => a_project.cmp(b_project),
+                (a_project, b_project) => b_project.is_some().cmp(&a_project.is_some()),
+            },
+            TaskSort::Deadline => match (&a.deadline, &b.deadline) {
+                (Some(a_deadline), Some(b_deadline)) => a_deadline.due.cmp(&b_deadline.due),
+                (a_deadline, b_deadline) => b_deadline.is_some().cmp(&a_deadline.is_some()),
+            },
+        };
+    }
+
+    // Returns matching tasks together with their index in the full list
+    pub fn apply<'a>(&self, tasks: &'a [Task]) -> Vec<(usize, &'a Task)> {
+        let mut visible: Vec<(usize, &Task)> = tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| self.matches(task))
+            .collect();
+        visible.sort_by(|(_, a), (_, b)| self.compare(a, b));
+        return visible;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{TaskSort, TaskView};
+    use crate::task::{Priority, Task};
+
+    fn task(name: &str, tags: &[&str], project: Option<&str>, priority: &str) -> Task {
+        return serde_json::from_value(json!({
+            "is_regular": false,
+            "reward_system": "SingularTransfer",
+            "reward": 1.0,
+            "description": "",
+            "name": name,
+            "tags": tags,
+            "project": project,
+            "priority": priority,
+        }))
+        .unwrap();
+    }
+
+    fn tasks() -> Vec<Task> {
+        return vec![
+            task("write", &["work"], Some("book"), "High"),
+            task("gym", &["health"], None, "Normal"),
+            task("edit", &["work"], Some("book"), "Urgent"),
+            task("invoice", &["work"], Some("admin"), "Low"),
+        ];
+    }
+
+    fn names(view: &TaskView, tasks: &[Task]) -> Vec<(usize, String)> {
+        return view
+            .apply(tasks)
+            .into_iter()
+            .map(|(i, task)| (i, task.name.clone()))
+            .collect();
+    }
+
+    #[test]
+    fn filters_keep_the_indices_of_the_full_list() {
+        let tasks = tasks();
+        let view = TaskView {
+            tag: Some("work".to_string()),
+            min_priority: Some(Priority::High),
+            ..TaskView::default()
+        };
+        assert_eq!(names(&view, &tasks), vec![(0, "write".to_string()), (2, "edit".to_string())]);
+        assert!(view.is_filtering());
+        assert!(!TaskView::default().is_filtering());
+    }
+
+    #[test]
+    fn sorting_puts_tasks_without_a_value_last() {
+        let tasks = tasks();
+        let by_project = TaskView {
+            sort: TaskSort::Project,
+            ..TaskView::default()
+        };
+        let order: Vec<usize> = by_project.apply(&tasks).into_iter().map(|(i, _)| i).collect();
+        assert_eq!(order, vec![3, 0, 2, 1]);
+        let by_priority = TaskView {
+            sort: TaskSort::Priority,
+            ..TaskView::default()
+        };
+        let order: Vec<usize> = by_priority.apply(&tasks).into_iter().map(|(i, _)| i).collect();
+        assert_eq!(order, vec![2, 0, 1, 3]);
+        assert!("".parse::<TaskSort>().unwrap() == TaskSort::Index);
+        assert!("size".parse::<TaskSort>().is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli_utils::{get_line, get_line_with_condition};
+use crate::currency::format_amount;
+use crate::deadline::Deadline;
+use crate::task::Task;
+
+// A named task with its progress reset, holding the preset reward, schedule, tags and so on
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TaskTemplate {
+    pub name: String,
+    pub task: Task,
+}
+
+impl TaskTemplate {
+    pub fn from_task(name: String, task: &Task) -> TaskTemplate {
+        return TaskTemplate {
+            name,
+            task: task.fresh_copy(),
+        };
+    }
+
+    // Asks only for what usually differs between tasks made from the same template
+    pub fn instantiate_from_cli(&self) -> Option<Task> {
+        let mut task = self.task.fresh_copy();
+        task.name = get_line_with_condition(Some("Enter the name of the task: "), |line| {
+            !line.trim().is_empty()
+        })?;
+        let description = get_line(Some(&f!(
+            "Enter the description of the task (empty keeps \"{}\"): ",
+            task.description
+        )))?;
+        if !description.trim().is_empty() {
+            task.description = description;
+        }
+        let reward = get_line_with_condition(
+            Some(&f!(
+                "Enter the reward amount (empty keeps {}): ",
+                format_amount(task.reward, &task.currency)
+            )),
+            |line| line.trim().is_empty() || line.trim().parse::<f64>().map_or(false, f64::is_finite),
+        )?;
+        if !reward.trim().is_empty() {
+            task.reward = reward.trim().parse().unwrap();
+        }
+        task.deadline = Deadline::init_deadline_from_cli()?;
+        return Some(task);
+    }
+}
+
+impl Display for TaskTemplate {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        return write!(f, "{}: {}", self.name, self.task.summary_line());
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+pub const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+// Accepts "YYYY-MM-DD HH:MM", or just "HH:MM" for a time today
+pub fn parse_date_time(line: &str) -> Option<DateTime<Local>> {
+    let naive = match NaiveDateTime::parse_from_str(line.trim(), DATE_TIME_FORMAT) {
+        Ok(naive) => naive,
+        Err(_) => Local::now()
+            .date_naive()
+            .and_time(NaiveTime::parse_from_str(line.trim(), "%H:%M").ok()?),
+    };
+    return Local.from_local_datetime(&naive).earliest();
+}
+
+pub fn start_of_day(date: NaiveDate) -> DateTime<Local> {
+    return start_of_day_in(&Local, date);
+}
+
+// Some time zones skip midnight when they switch to summer time, the day then starts at the first valid time
+fn start_of_day_in<Tz: TimeZone>(timezone: &Tz, date: NaiveDate) -> DateTime<Tz> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    return (0..24 * 4)
+        .map(|quarter| midnight + Duration::minutes(15 * quarter))
+        .find_map(|time| timezone.from_local_datetime(&time).earliest())
+        .expect(&f!("Could not find the start of {} in the local timezone", date));
+}
+
+pub fn first_day_of_next_month(date: NaiveDate) -> NaiveDate {
+    return if date.month() == 12 {
+        NaiveDate::from_ymd_opt(date.year() + 1, 1, 1).unwrap()
+    } else {
+        NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1).unwrap()
+    };
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let total_minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (
+        total_minutes / (60 * 24),
+        total_minutes / 60 % 24,
+        total_minutes % 60,
+    );
+    return if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    };
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl CalendarPeriod {
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        return match self {
+            CalendarPeriod::Day => date,
+            CalendarPeriod::Week => {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            CalendarPeriod::Month => date.with_day(1).unwrap(),
+        };
+    }
+
+    pub fn next_period_start(&self, date: NaiveDate) -> NaiveDate {
+        let period_start = self.period_start(date);
+        return match self {
+            CalendarPeriod::Day => period_start + Duration::days(1),
+            CalendarPeriod::Week => period_start + Duration::days(7),
+            CalendarPeriod::Month => first_day_of_next_month(period_start),
+        };
+    }
+
+    pub fn name(&self) -> &'static str {
+        return match self {
+            CalendarPeriod::Day => "day",
+            CalendarPeriod::Week => "week",
+            CalendarPeriod::Month => "month",
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+    use super::{format_duration, start_of_day_in, CalendarPeriod};
+
+    // UTC-4 that jumps to UTC-3 at midnight, like America/Santiago does
+    #[derive(Clone, Copy)]
+    struct SkippedMidnight;
+
+    impl TimeZone for SkippedMidnight {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> SkippedMidnight {
+            return SkippedMidnight;
+        }
+
+        fn offset_from_local_date(&self, _: &NaiveDate) -> LocalResult<FixedOffset> {
+            return LocalResult::Single(FixedOffset::west_opt(3 * 3600).unwrap());
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            if local.hour() == 0 {
+                return LocalResult::None;
+            }
+            return LocalResult::Single(FixedOffset::west_opt(3 * 3600).unwrap());
+        }
+
+        fn offset_from_utc_date(&self, _: &NaiveDate) -> FixedOffset {
+            return FixedOffset::west_opt(3 * 3600).unwrap();
+        }
+
+        fn offset_from_utc_datetime(&self, _: &NaiveDateTime) -> FixedOffset {
+            return FixedOffset::west_opt(3 * 3600).unwrap();
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    }
+
+    #[test]
+    fn a_skipped_midnight_starts_the_day_at_the_first_valid_time() {
+        let start = start_of_day_in(&SkippedMidnight, date(2026, 9, 6));
+        assert_eq!(start.naive_local(), date(2026, 9, 6).and_hms_opt(1, 0, 0).unwrap());
+        let utc_start = start_of_day_in(&chrono::Utc, date(2026, 9, 6));
+        assert_eq!(utc_start.naive_local(), date(2026, 9, 6).and_hms_opt(0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn periods_start_on_mondays_and_first_days() {
+        let sunday = date(2026, 11, 1);
+        assert_eq!(CalendarPeriod::Week.period_start(sunday), date(2026, 10, 26));
+        assert_eq!(CalendarPeriod::Week.next_period_start(sunday), date(2026, 11, 2));
+        assert_eq!(CalendarPeriod::Month.next_period_start(date(2026, 12, 31)), date(2027, 1, 1));
+        assert_eq!(CalendarPeriod::Day.next_period_start(sunday), date(2026, 11, 2));
+    }
+
+    #[test]
+    fn durations_show_the_largest_units() {
+        assert_eq!(format_duration(chrono::Duration::minutes(59)), "59m");
+        assert_eq!(format_duration(chrono::Duration::minutes(61)), "1h 1m");
+        assert_eq!(format_duration(chrono::Duration::minutes(60 * 25 + 5)), "1d 1h 5m");
+        assert_eq!(format_duration(chrono::Duration::minutes(-5)), "0m");
+    }
+}
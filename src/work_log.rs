@@ -0,0 +1,212 @@
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WorkSession {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    // None of the task's work processes were running for most of the sampled time
+    #[serde(default)]
+    pub idle: bool,
+}
+
+impl WorkSession {
+    pub fn duration(&self) -> Duration {
+        return self.end.signed_duration_since(self.start);
+    }
+}
+
+pub fn cap_duration(duration: Duration, max_session: Option<Duration>) -> Duration {
+    return match max_session {
+        Some(max_session) => duration.min(max_session),
+        None => duration,
+    };
+}
+
+// Whether the work processes were seen while the session ran, each sample stands for the time since the one before
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct ProcessSamples {
+    pub last_sample_at: Option<DateTime<Local>>,
+    pub active_seconds: i64,
+    pub idle_seconds: i64,
+}
+
+impl ProcessSamples {
+    pub fn record(&mut self, session_start: DateTime<Local>, now: DateTime<Local>, idle: bool) {
+        let from = self.last_sample_at.map_or(session_start, |last| last.max(session_start));
+        let seconds = now.signed_duration_since(from).num_seconds().max(0);
+        if idle {
+            self.idle_seconds += seconds;
+        } else {
+            self.active_seconds += seconds;
+        }
+        self.last_sample_at = Some(now);
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>) -> bool {
+        return self
+            .last_sample_at
+            .map_or(true, |last| now.signed_duration_since(last) >= Duration::minutes(1));
+    }
+
+    pub fn is_mostly_idle(&self) -> bool {
+        return self.idle_seconds > self.active_seconds;
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TimeCorrection {
+    pub made_at: DateTime<Local>,
+    pub description: String,
+}
+
+// Finished sessions of an hourly task that have not been paid out yet
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct WorkLog {
+    pub sessions: Vec<WorkSession>,
+    // Manual entries and edits stay here after the sessions are paid out
+    #[serde(default)]
+    pub corrections: Vec<TimeCorrection>,
+    // Sessions that were already paid out, kept so the same time can not be logged and paid twice
+    #[serde(default)]
+    pub paid_sessions: Vec<WorkSession>,
+    #[serde(default)]
+    pub running_samples: ProcessSamples,
+}
+
+impl WorkLog {
+    pub fn close_session(&mut self, start: DateTime<Local>, end: DateTime<Local>, idle: bool) {
+        self.sessions.push(WorkSession { start, end, idle });
+    }
+
+    // Flags the running session on its samples, plus one taken at the end when the processes were checked then.
+    // Returns whether the session is idle.
+    pub fn close_running_session(
+        &mut self,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        idle_at_end: Option<bool>,
+    ) -> bool {
+        if let Some(idle) = idle_at_end {
+            self.running_samples.record(start, end, idle);
+        }
+        let idle = self.running_samples.is_mostly_idle();
+        self.running_samples = ProcessSamples::default();
+        self.close_session(start, end, idle);
+        return idle;
+    }
+
+    pub fn logged_duration(&self) -> Duration {
+        return self
+            .sessions
+            .iter()
+            .fold(Duration::zero(), |total, session| total + session.duration());
+    }
+
+    // Idle sessions are skipped unless they are paid, the limit covers all unpaid sessions together
+    pub fn payable_duration(&self, max_session: Option<Duration>, pay_idle: bool) -> Duration {
+        let worked = self
+            .sessions
+            .iter()
+            .filter(|session| pay_idle || !session.idle)
+            .fold(Duration::zero(), |total, session| total + session.duration());
+        return cap_duration(worked, max_session);
+    }
+
+    // Sessions are half-open, so one may start exactly when another ends
+    pub fn overlaps(&self, start: DateTime<Local>, end: DateTime<Local>) -> bool {
+        return self
+            .sessions
+            .iter()
+            .chain(self.paid_sessions.iter())
+            .any(|session| start < session.end && session.start < end);
+    }
+
+    pub fn idle_session_count(&self) -> usize {
+        return self.sessions.iter().filter(|session| session.idle).count();
+    }
+
+    pub fn record_correction(&mut self, description: String) {
+        println!("{}", description);
+        self.corrections.push(TimeCorrection {
+            made_at: Local::now(),
+            description,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.sessions.is_empty();
+    }
+
+    // Called once the sessions are paid out
+    pub fn clear(&mut self) {
+        self.paid_sessions.append(&mut self.sessions);
+    }
+}
+
+// Sums whole seconds before converting, so no session loses its partial minute
+pub fn hours_in(duration: Duration) -> f64 {
+    return duration.num_seconds().max(0) as f64 / 3600.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, TimeZone};
+
+    use super::WorkLog;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        return Local.with_ymd_and_hms(2026, 10, 12, hour, minute, 0).unwrap();
+    }
+
+    #[test]
+    fn sessions_may_touch_but_not_overlap() {
+        let mut work_log = WorkLog::default();
+        work_log.close_session(at(9, 0), at(10, 0), false);
+        assert!(work_log.overlaps(at(9, 30), at(11, 0)));
+        assert!(work_log.overlaps(at(8, 0), at(12, 0)));
+        assert!(!work_log.overlaps(at(10, 0), at(11, 0)));
+        assert!(!work_log.overlaps(at(8, 0), at(9, 0)));
+    }
+
+    #[test]
+    fn the_session_limit_covers_all_unpaid_sessions() {
+        let mut work_log = WorkLog::default();
+        work_log.close_session(at(9, 0), at(10, 0), false);
+        work_log.close_session(at(11, 0), at(12, 30), true);
+        let limit = Some(chrono::Duration::hours(2));
+        assert_eq!(work_log.payable_duration(limit, true), chrono::Duration::hours(2));
+        assert_eq!(work_log.payable_duration(limit, false), chrono::Duration::hours(1));
+        assert_eq!(work_log.payable_duration(None, true), chrono::Duration::minutes(150));
+    }
+
+    #[test]
+    fn sessions_are_flagged_on_the_sampled_idle_share() {
+        let mut work_log = WorkLog::default();
+        work_log.running_samples.record(at(9, 0), at(9, 30), true);
+        work_log.running_samples.record(at(9, 0), at(11, 0), true);
+        assert!(work_log.close_running_session(at(9, 0), at(11, 10), Some(false)));
+        work_log.running_samples.record(at(12, 0), at(12, 50), false);
+        assert!(!work_log.close_running_session(at(12, 0), at(13, 0), Some(true)));
+        let flags: Vec<bool> = work_log.sessions.iter().map(|session| session.idle).collect();
+        assert_eq!(flags, vec![true, false]);
+    }
+
+    #[test]
+    fn samples_are_taken_at_most_once_a_minute() {
+        let mut work_log = WorkLog::default();
+        assert!(work_log.running_samples.is_due(at(9, 0)));
+        work_log.running_samples.record(at(9, 0), at(9, 0), false);
+        assert!(!work_log.running_samples.is_due(at(9, 0)));
+        assert!(work_log.running_samples.is_due(at(9, 1)));
+    }
+
+    #[test]
+    fn paid_sessions_still_count_as_logged() {
+        let mut work_log = WorkLog::default();
+        work_log.close_session(at(9, 0), at(10, 0), false);
+        work_log.clear();
+        assert!(work_log.is_empty());
+        assert!(work_log.overlaps(at(9, 0), at(10, 0)));
+    }
+}